@@ -1,11 +1,8 @@
 use axum::{extract::Path, http::StatusCode, response::IntoResponse, routing::get, Router};
-use json_resp::{JsonError, JsonResponse};
+use json_resp::{JsonError, JsonResponse, RouterExt};
 
 #[derive(JsonError)]
 enum AppErrors {
-    #[json_error(request, status = StatusCode::NOT_FOUND, code = "not-found")]
-    NotFound,
-
     #[json_error(request, status = StatusCode::CONFLICT, code = "received-odd-number", hint = "Try an even number")]
     OddNotAllowed(&'static str),
 
@@ -25,10 +22,6 @@ async fn number(Path(number): Path<u64>) -> impl IntoResponse {
     }
 }
 
-async fn not_found() -> impl IntoResponse {
-    AppErrors::NotFound
-}
-
 async fn index() -> impl IntoResponse {
     JsonResponse::with_content("Hello")
 }
@@ -38,7 +31,8 @@ async fn main() {
     let router = Router::new()
         .route("/", get(index))
         .route("/:number", get(number))
-        .fallback(not_found);
+        // 404 for unknown routes and 405 for unknown methods
+        .json_fallbacks();
 
     axum::Server::bind(&"127.0.0.1:3000".parse().unwrap())
         .serve(router.into_make_service())
@@ -1,10 +1,10 @@
-use axum::{extract::Path, http::StatusCode, response::IntoResponse, routing::get, Router};
+use axum::{extract::Path, http::StatusCode, routing::get, Router};
 use schemas::HelloResponse;
 use serde::de::{value, Error};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use json_resp::{JsonError, JsonResponse, CombineErrors};
+use json_resp::{JsonError, JsonSuccess};
 
 mod schemas {
     use serde::Serialize;
@@ -17,12 +17,22 @@ mod schemas {
     }
 }
 
+#[derive(JsonSuccess)]
+enum HelloResponses {
+    Hello(HelloResponse),
+
+    // The second field is used as the meta
+    #[json_success(status=StatusCode::CREATED, description="Hello with a meta")]
+    WithMeta(HelloResponse, HelloResponse),
+}
+
 #[derive(JsonError)]
 #[json_error(internal_code="500 internal")]
 enum AppErrors {
     #[json_error(request, status=StatusCode::NOT_FOUND, code="404 not-found", description="The page does not exist")]
     NotFound,
     
+    /// Doc comments are used as the description too
     #[json_error(request, status=StatusCode::NOT_FOUND, code="4042 not-found")]
     NotFound2,
 
@@ -48,37 +58,32 @@ struct AppApi;
     get,
     path = "/{name}",
     responses(
-        // JsonResponse has to be inlined otherwise it will cause naming conflicts
-        (status=200, body=inline(JsonResponse<schemas::HelloResponse>)), 
-        (status=201, body=inline(JsonResponse<schemas::HelloResponse, schemas::HelloResponse>)), 
-        // CombineErrors can be used when 2 errors have the same status
-        CombineErrors::<AppErrorsOai::NotFound, AppErrorsOai::NotFound2>,
-        AppErrorsOai::InternalError
+        HelloResponses,
+        // All documents every error of the enum, errors with the same status are combined.
+        // CombineErrors::<AppErrorsOai::NotFound, AppErrorsOai::NotFound2> can be used to pick some
+        AppErrorsOai::All
     )
 )]
-async fn index(Path(name): Path<String>) -> impl IntoResponse {
+async fn index(Path(name): Path<String>) -> Result<HelloResponses, AppErrors> {
     match name.as_str() {
         "500" => Err(AppErrors::InternalError),
         "501" => Err(AppErrors::AnotherInternalError(value::Error::custom("Error"))),
         "404" => Err(AppErrors::NotFound),
         "4042" => Err(AppErrors::NotFound2),
-        "meta" => Ok((
-            StatusCode::CREATED,
-            JsonResponse::with_content(HelloResponse {
+        "meta" => Ok(HelloResponses::WithMeta(
+            HelloResponse {
                 number: 1,
                 string: name.to_string(),
-            })
-            .meta(HelloResponse {
+            },
+            HelloResponse {
                 number: 2,
                 string: name.to_string(),
-            }),
-        )
-            .into_response()),
-        _ => Ok(JsonResponse::with_content(HelloResponse {
+            },
+        )),
+        _ => Ok(HelloResponses::Hello(HelloResponse {
             number: 1,
             string: name.to_string(),
-        })
-        .into_response()),
+        })),
     }
 }
 
@@ -1,21 +1,23 @@
-use super::types::JsonError;
+use super::report::Level;
+use super::types::{extract_docs, InternalErrorGroup, JsonError};
 use crate::ctxt::Ctxt;
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
-    punctuated::Punctuated, token::Comma, Attribute, Data, DataEnum, Expr, ExprAssign, ExprParen,
-    Lit, Variant,
+    punctuated::Punctuated, token::Comma, Attribute, Data, DataEnum, DataStruct, Expr, ExprLit,
+    ExprParen, ExprTuple, Lit, Variant, Visibility,
 };
 
 pub fn expand_derive(input: &syn::DeriveInput) -> Result<TokenStream, Vec<syn::Error>> {
     let ctxt = Ctxt::new();
 
-    let config = Config::from_attrs(&input.attrs);
+    let config = Config::from_attrs(&input.attrs, &ctxt);
 
     let qoute = match &input.data {
         Data::Enum(DataEnum { variants, .. }) => expand_derive_enum(input, config, variants, &ctxt),
+        Data::Struct(data) => expand_derive_struct(input, config, data, &ctxt),
         _ => {
-            ctxt.error_spanned_by(input, "Expected `enum`");
+            ctxt.error_spanned_by(input, "Expected `enum` or `struct`");
             None
         }
     };
@@ -33,33 +35,65 @@ fn expand_derive_enum(
     variants: &Punctuated<Variant, Comma>,
     ctxt: &Ctxt,
 ) -> Option<TokenStream> {
+    let json_errors = JsonErrors::from_variants(input.ident.clone(), config, variants, ctxt)?;
+    Some(expand_json_errors(input, json_errors))
+}
+
+fn expand_derive_struct(
+    input: &syn::DeriveInput,
+    config: Config,
+    data: &DataStruct,
+    ctxt: &Ctxt,
+) -> Option<TokenStream> {
+    let json_errors = JsonErrors::from_struct(input, config, data, ctxt)?;
+    Some(expand_json_errors(input, json_errors))
+}
+
+fn expand_json_errors(input: &syn::DeriveInput, json_errors: JsonErrors) -> TokenStream {
     let name = &input.ident;
-    let json_errors = match JsonErrors::from_variants(name.clone(), config, variants, ctxt) {
-        Some(json_errors) => json_errors,
-        None => return None,
-    };
     let generics = &input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let gen = quote! {
-        impl #impl_generics ::json_resp::__private::IntoResponse for #name #ty_generics #where_clause {
-            fn into_response(self) -> ::json_resp::__private::Response {
+        impl #impl_generics ::json_resp::__private::Render for #name #ty_generics #where_clause {
+            fn render<__S: ::json_resp::__private::Sink>(self) -> __S {
                 match self{
                     #json_errors
                 }
             }
         }
+
+        impl #impl_generics ::json_resp::__private::IntoResponse for #name #ty_generics #where_clause {
+            fn into_response(self) -> ::json_resp::__private::Response {
+                ::json_resp::__private::Render::render(self)
+            }
+        }
     };
 
     #[cfg(feature = "openapi")]
     let gen = {
-        let docs_name = Ident::new(&format!("{}Oai", name), proc_macro2::Span::call_site());
+        let docs_name = match &json_errors.config.docs_module {
+            Some(docs_name) => docs_name.clone(),
+            None => Ident::new(&format!("{}Oai", name), proc_macro2::Span::call_site()),
+        };
+        let docs_vis = match &json_errors.config.docs_vis {
+            Some(docs_vis) => quote!(#docs_vis),
+            None => quote!(pub(crate)),
+        };
 
         let utoipa_inner = json_errors.into_utoipa_expand();
+        let all = utoipa_inner.all_ident();
 
         // Utoipa impls
         quote! {
             #gen
-            pub(crate) mod #docs_name{
+
+            impl #impl_generics ::json_resp::__private::utoipa::ErrorDocs for #name #ty_generics #where_clause {
+                type All = #docs_name::#all;
+                const NAME: &'static str = stringify!(#name);
+            }
+
+            #[allow(non_snake_case)]
+            #docs_vis mod #docs_name{
                 use super::*;
 
                 #utoipa_inner
@@ -67,59 +101,124 @@ fn expand_derive_enum(
         }
     };
 
-    Some(gen)
+    gen
 }
 
 pub struct Config {
     pub internal_error_code: String,
+    /// Level used to report request errors which don't define their own
+    pub request_level: Option<Level>,
+    /// Prefix of the openapi component names, the name of the type followed by `_` by default
+    pub schema_prefix: Option<String>,
+    /// Name of the docs module, `{Name}Oai` by default
+    pub docs_module: Option<Ident>,
+    /// Visibility of the docs module, `pub(crate)` by default
+    pub docs_vis: Option<Visibility>,
+    /// Doc comments of the type, used to describe its internal errors
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+    pub docs: Option<String>,
 }
 
 impl Config {
-    fn from_attrs(attrs: &Vec<Attribute>) -> Self {
-        let attrs = attrs
+    fn from_attrs(attrs: &[Attribute], ctxt: &Ctxt) -> Self {
+        let mut config = Self {
+            internal_error_code: String::from("internal-error"),
+            request_level: None,
+            schema_prefix: None,
+            docs_module: None,
+            docs_vis: None,
+            docs: extract_docs(attrs),
+        };
+
+        // Anything other than the known assignments is ignored, structs share this attribute
+        // with their error definition
+        let assigns = attrs
             .iter()
-            .filter(|attr| {
-                attr.path.get_ident().is_some()
-                    && attr.path.get_ident().unwrap().to_string().as_str() == "json_error"
-            })
-            .next()
-            .and_then(|attr| syn::parse2::<ExprParen>(attr.tokens.clone()).ok())
-            .and_then(|attr| syn::parse2::<ExprAssign>(attr.expr.to_token_stream()).ok())
-            .and_then(Self::extract_internal_code);
-
-        match attrs {
-            Some(internal_code) => Self {
-                internal_error_code: internal_code,
-            },
-            None => {
-                return Self {
-                    internal_error_code: String::from("internal-error"),
-                };
+            .filter(|attr| attr.path.is_ident("json_error"))
+            .flat_map(|attr| extract_exprs(attr.tokens.clone()))
+            .filter_map(|expr| match expr {
+                Expr::Assign(assign) => Some(assign),
+                _ => None,
+            });
+
+        for assign in assigns {
+            let key = match &*assign.left {
+                Expr::Path(key) => key.path.get_ident().map(|k| k.to_string()),
+                _ => None,
+            };
+            let value = match &*assign.right {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => Some(lit),
+                _ => None,
+            };
+
+            match (key.as_deref(), value) {
+                (Some("internal_code"), Some(value)) => config.internal_error_code = value.value(),
+                (Some("request_level"), Some(value)) => match Level::from_lit(value) {
+                    Some(level) => config.request_level = Some(level),
+                    None => ctxt.error_spanned_by(
+                        value,
+                        "level should be one of `error`, `warn`, `info`, `debug` or `trace`",
+                    ),
+                },
+                (Some("schema_prefix"), Some(value)) => config.schema_prefix = Some(value.value()),
+                (Some("docs_module"), Some(value)) => match value.parse::<Ident>() {
+                    Ok(ident) => config.docs_module = Some(ident),
+                    Err(_) => ctxt.error_spanned_by(value, "docs_module should be an identifier"),
+                },
+                (Some("docs_vis"), Some(value)) => match value.parse::<Visibility>() {
+                    Ok(vis) => config.docs_vis = Some(vis),
+                    Err(_) => ctxt.error_spanned_by(
+                        value,
+                        "docs_vis should be a visibility, e.g. `pub` or `pub(super)`",
+                    ),
+                },
+                _ => {}
             }
         }
+
+        config
     }
+}
 
-    fn extract_internal_code(assign: ExprAssign) -> Option<String> {
-        match *assign.left {
-            Expr::Path(key)
-                if key.path.get_ident().map(|k| k.to_string())
-                    == Some("internal_code".to_string()) => {}
-            _ => return None,
-        };
+/// Keys of the container attribute read by `Config`
+const CONFIG_KEYS: &[&str] = &[
+    "internal_code",
+    "request_level",
+    "schema_prefix",
+    "docs_module",
+    "docs_vis",
+];
 
-        match *assign.right {
-            Expr::Lit(lit) => match lit.lit {
-                Lit::Str(lit) => Some(lit.value()),
-                _ => None,
-            },
-            _ => None,
-        }
+fn is_config_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Assign(assign) => match &*assign.left {
+            Expr::Path(key) => key
+                .path
+                .get_ident()
+                .is_some_and(|key| CONFIG_KEYS.iter().any(|config| key == config)),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+pub(crate) fn extract_exprs(tokens: TokenStream) -> Vec<Expr> {
+    if let Ok(tuple) = syn::parse2::<ExprTuple>(tokens.clone()) {
+        return tuple.elems.into_iter().collect();
+    }
+
+    match syn::parse2::<ExprParen>(tokens) {
+        Ok(paren) => vec![*paren.expr],
+        Err(_) => Vec::new(),
     }
 }
 
 pub struct JsonErrors {
     ident: Ident,
-    internal_err_code: String,
+    is_struct: bool,
+    config: Config,
     errors: Vec<JsonError>,
 }
 
@@ -132,22 +231,84 @@ impl JsonErrors {
     ) -> Option<Self> {
         let mut ret = Vec::new();
         for variant in variants.iter() {
-            match JsonError::from_variant(variant, ctxt) {
-                Some(err) => ret.push(err),
-                None => {}
+            if let Some(err) = JsonError::from_variant(variant, ctxt) {
+                ret.push(err);
             }
         }
         Some(Self {
             ident,
-            internal_err_code: config.internal_error_code,
+            is_struct: false,
+            config,
             errors: ret,
         })
     }
 
+    /// A struct is handled like an enum with a single variant, taking its attribute from the
+    /// container
+    pub(crate) fn from_struct(
+        input: &syn::DeriveInput,
+        config: Config,
+        data: &DataStruct,
+        ctxt: &Ctxt,
+    ) -> Option<Self> {
+        if !input
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("json_error"))
+        {
+            ctxt.error_spanned_by(&input.ident, "Structs should have a json_error attribute");
+            return None;
+        }
+
+        // The container keys are already read by `Config`, the rest defines the error
+        let attrs = input
+            .attrs
+            .iter()
+            .filter_map(|attr| {
+                if !attr.path.is_ident("json_error") {
+                    return Some(attr.clone());
+                }
+                let exprs = extract_exprs(attr.tokens.clone())
+                    .into_iter()
+                    .filter(|expr| !is_config_expr(expr))
+                    .collect::<Vec<_>>();
+                if exprs.is_empty() {
+                    return None;
+                }
+                let mut attr = attr.clone();
+                attr.tokens = quote!((#(#exprs),*));
+                Some(attr)
+            })
+            .collect();
+
+        let variant = Variant {
+            attrs,
+            ident: input.ident.clone(),
+            fields: data.fields.clone(),
+            discriminant: None,
+        };
+
+        Some(Self {
+            ident: input.ident.clone(),
+            is_struct: true,
+            config,
+            errors: vec![JsonError::from_variant(&variant, ctxt)?],
+        })
+    }
+
     #[cfg(feature = "openapi")]
     pub(crate) fn into_utoipa_expand(self) -> JsonErrorUtoipaTypes {
+        // Structs are prefixed too, their internal groups would collide otherwise
+        let schema_prefix = match self.config.schema_prefix {
+            Some(prefix) => prefix,
+            None => format!("{}_", self.ident),
+        };
+
         JsonErrorUtoipaTypes {
-            internal_err_code: self.internal_err_code,
+            internal_err_code: self.config.internal_error_code,
+            schema_prefix,
+            is_struct: self.is_struct,
+            docs: self.config.docs,
             errors: self.errors,
         }
     }
@@ -156,33 +317,75 @@ impl JsonErrors {
 impl ToTokens for JsonErrors {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         for err_type in &self.errors {
-            let cond = err_type.expand_match_condition(&self.ident, &self.internal_err_code);
+            let cond = err_type.expand_match_condition(&self.ident, self.is_struct, &self.config);
             tokens.append_all(quote!(#cond,));
         }
     }
 }
 
+#[cfg_attr(not(feature = "openapi"), allow(dead_code))]
 pub struct JsonErrorUtoipaTypes {
     internal_err_code: String,
+    schema_prefix: String,
+    is_struct: bool,
+    docs: Option<String>,
     errors: Vec<JsonError>,
 }
 
+#[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+impl JsonErrorUtoipaTypes {
+    /// Name of the type documenting all the responses, a variant named `All` takes its name
+    pub(crate) fn all_ident(&self) -> Ident {
+        let name = match self.errors.iter().any(|err| err.is_named("All")) {
+            true => "__All",
+            false => "All",
+        };
+        Ident::new(name, proc_macro2::Span::call_site())
+    }
+}
+
 impl ToTokens for JsonErrorUtoipaTypes {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let mut has_internal_error = false;
-
         for err_type in &self.errors {
-            if let Some(gen) = err_type.expand_utoipa_response() {
+            if let Some(gen) = err_type.expand_utoipa_response(&self.schema_prefix, self.is_struct)
+            {
                 tokens.append_all(gen);
-            } else {
-                has_internal_error = true;
             }
         }
 
-        if has_internal_error {
-            tokens.append_all(JsonError::expand_utoipa_internal_error(
-                &self.internal_err_code,
-            ));
+        let groups = InternalErrorGroup::from_errors(&self.errors, &self.internal_err_code);
+        for group in &groups {
+            tokens.append_all(
+                group.expand_utoipa_internal_error(&self.schema_prefix, self.docs.as_deref()),
+            );
         }
+
+        let all = self.all_ident();
+        let hidden = match all == "All" {
+            true => quote!(),
+            false => quote!(#[doc(hidden)]),
+        };
+        let responses = self
+            .errors
+            .iter()
+            .filter_map(|err| err.expand_utoipa_responses_path())
+            .chain(groups.iter().map(|group| group.name().into_token_stream()));
+
+        // All the responses of the type, the ones sharing a status are documented as either of them
+        tokens.append_all(quote! {
+            #hidden
+            pub struct #all;
+
+            impl ::json_resp::__private::utoipa::IntoResponses for #all {
+                fn responses() -> std::collections::BTreeMap<
+                    String,
+                    ::json_resp::__private::utoipa::RefOr<::json_resp::__private::utoipa::Response>,
+                > {
+                    ::json_resp::__private::utoipa::merge_responses([
+                        #(<#responses as ::json_resp::__private::utoipa::IntoResponses>::responses(),)*
+                    ])
+                }
+            }
+        });
     }
 }
@@ -0,0 +1,184 @@
+use crate::ctxt::Ctxt;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, ExprCall, Ident, Lit, LitStr};
+
+/// A header defined with `header("Name" = value)`, value can be a literal or a field of the
+/// error's content
+#[derive(Clone)]
+pub(crate) struct HeaderExpr {
+    name: LitStr,
+    value: HeaderValueExpr,
+}
+
+#[derive(Clone)]
+enum HeaderValueExpr {
+    Lit(LitStr),
+    Field(Ident),
+}
+
+impl HeaderExpr {
+    /// Parse all the headers defined in a single `header(...)` call
+    pub(crate) fn from_call(call: &ExprCall, naive: bool, ctxt: &Ctxt) -> Vec<Self> {
+        let mut headers = Vec::new();
+
+        for arg in &call.args {
+            let assign = if let Expr::Assign(assign) = arg {
+                assign
+            } else {
+                ctxt.error_spanned_by(arg, "Headers should be in form of `\"Name\" = value`.");
+                continue;
+            };
+
+            let name = match &*assign.left {
+                Expr::Lit(lit) => match &lit.lit {
+                    Lit::Str(name) if is_valid_name(&name.value()) => name.clone(),
+                    _ => {
+                        ctxt.error_spanned_by(&assign.left, "Invalid header name");
+                        continue;
+                    }
+                },
+                _ => {
+                    ctxt.error_spanned_by(&assign.left, "Header name should be a str");
+                    continue;
+                }
+            };
+
+            let value = match &*assign.right {
+                Expr::Lit(lit) => {
+                    let value = match &lit.lit {
+                        Lit::Str(value) => value.value(),
+                        Lit::Int(value) => value.base10_digits().to_string(),
+                        _ => {
+                            ctxt.error_spanned_by(
+                                &assign.right,
+                                "Header value should be a str, a number or a field",
+                            );
+                            continue;
+                        }
+                    };
+                    if !is_valid_value(&value) {
+                        ctxt.error_spanned_by(&assign.right, "Invalid header value");
+                        continue;
+                    }
+                    HeaderValueExpr::Lit(LitStr::new(&value, lit.lit.span()))
+                }
+                Expr::Path(path) if path.path.get_ident().is_some() => {
+                    if naive {
+                        ctxt.error_spanned_by(
+                            &assign.right,
+                            "Fields can only be used as header values of errors with a content",
+                        );
+                        continue;
+                    }
+                    HeaderValueExpr::Field(path.path.get_ident().unwrap().clone())
+                }
+                _ => {
+                    ctxt.error_spanned_by(
+                        &assign.right,
+                        "Header value should be a str, a number or a field",
+                    );
+                    continue;
+                }
+            };
+
+            headers.push(Self { name, value });
+        }
+
+        headers
+    }
+
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+    pub(crate) fn is_same_header(&self, other: &Self) -> bool {
+        self.name.value().eq_ignore_ascii_case(&other.name.value())
+    }
+
+    /// Expands to a statement building the headers and the builder call adding them to the
+    /// `JsonError`, headers have to be built before the content is moved into the response
+    pub(crate) fn expand_response_headers(
+        headers: &[Self],
+    ) -> (Option<TokenStream>, Option<TokenStream>) {
+        if headers.is_empty() {
+            return (None, None);
+        }
+
+        let header_map = Self::expand_header_map(headers);
+        (
+            Some(quote!(let headers = #header_map;)),
+            Some(quote!(.headers(headers))),
+        )
+    }
+
+    /// Expands to a block building a `HeaderMap` out of the headers, fields are read from `err`
+    fn expand_header_map(headers: &[Self]) -> TokenStream {
+        let inserts = headers.iter().map(|header| {
+            let name = header.name.value().to_ascii_lowercase();
+            let name = quote!(::json_resp::__private::HeaderName::from_static(#name));
+
+            match &header.value {
+                HeaderValueExpr::Lit(value) => quote! {
+                    headers.insert(
+                        #name,
+                        ::json_resp::__private::HeaderValue::from_static(#value),
+                    );
+                },
+                HeaderValueExpr::Field(field) => quote! {
+                    if let Ok(value) = ::json_resp::__private::HeaderValue::try_from(
+                        ::std::string::ToString::to_string(&err.#field),
+                    ) {
+                        headers.insert(#name, value);
+                    }
+                },
+            }
+        });
+
+        quote! {{
+            let mut headers = ::json_resp::__private::HeaderMap::new();
+            #(#inserts)*
+            headers
+        }}
+    }
+
+    /// Expands to the `.header(...)` calls documenting the headers on a utoipa `ResponseBuilder`
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+    pub(crate) fn expand_utoipa_headers(headers: &[Self]) -> TokenStream {
+        let headers = headers.iter().map(|header| {
+            let name = &header.name;
+            let enum_values = match &header.value {
+                HeaderValueExpr::Lit(value) => Some(quote! {
+                    .enum_values(Some([#value]))
+                    .example(Some(#value.into()))
+                }),
+                HeaderValueExpr::Field(_) => None,
+            };
+
+            quote! {
+                .header(
+                    #name,
+                    ::json_resp::__private::utoipa::HeaderBuilder::new()
+                        .schema(
+                            ::json_resp::__private::utoipa::ObjectBuilder::new()
+                                .schema_type(::json_resp::__private::utoipa::SchemaType::String)
+                                #enum_values
+                        )
+                        .build(),
+                )
+            }
+        });
+
+        quote!(#(#headers)*)
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+fn is_valid_value(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b == b'\t' || (0x20..0x7f).contains(&b))
+}
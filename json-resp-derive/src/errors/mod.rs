@@ -1,4 +1,9 @@
 mod expand;
+mod headers;
+mod report;
 mod types;
 
-pub(crate) use self::expand::expand_derive;
+pub(crate) use self::expand::{expand_derive, extract_exprs};
+pub(crate) use self::types::{
+    extract_docs, extract_ident_str, extract_lit_str, extract_status, StatusExpr,
+};
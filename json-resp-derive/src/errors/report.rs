@@ -0,0 +1,185 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, LitStr};
+
+/// Level used to log/trace an error, set with `level = "warn"`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub(crate) fn from_lit(lit: &LitStr) -> Option<Self> {
+        match lit.value().to_ascii_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "warn" => Some(Self::Warn),
+            "info" => Some(Self::Info),
+            "debug" => Some(Self::Debug),
+            "trace" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+
+    #[allow(unused)]
+    fn expand_log(&self) -> TokenStream {
+        match self {
+            Self::Error => quote!(::json_resp::__private::log::Level::Error),
+            Self::Warn => quote!(::json_resp::__private::log::Level::Warn),
+            Self::Info => quote!(::json_resp::__private::log::Level::Info),
+            Self::Debug => quote!(::json_resp::__private::log::Level::Debug),
+            Self::Trace => quote!(::json_resp::__private::log::Level::Trace),
+        }
+    }
+
+    #[allow(unused)]
+    fn expand_tracing(&self) -> TokenStream {
+        match self {
+            Self::Error => quote!(::json_resp::__private::tracing::Level::ERROR),
+            Self::Warn => quote!(::json_resp::__private::tracing::Level::WARN),
+            Self::Info => quote!(::json_resp::__private::tracing::Level::INFO),
+            Self::Debug => quote!(::json_resp::__private::tracing::Level::DEBUG),
+            Self::Trace => quote!(::json_resp::__private::tracing::Level::TRACE),
+        }
+    }
+}
+
+/// What is known about an error when it's being reported
+#[allow(unused)]
+pub(crate) struct Report<'a> {
+    pub level: Option<Level>,
+    pub internal: bool,
+    /// Name of the error, `Enum::Variant` or `Struct`
+    pub name: &'a TokenStream,
+    pub type_ident: &'a Ident,
+    /// `None` for structs
+    pub variant: Option<&'a Ident>,
+    pub code: &'a TokenStream,
+    /// Whether the content is bound to `err`
+    pub has_content: bool,
+    /// Whether an error id is bound to `error_id`
+    pub has_error_id: bool,
+}
+
+impl<'a> Report<'a> {
+    /// Expands to the statements reporting the error through the registered reporter and the
+    /// enabled integrations, the status is expected to be bound to `status`
+    #[allow(unused)]
+    pub(crate) fn expand(&self) -> TokenStream {
+        let reporter = self.expand_reporter();
+
+        let level = match self.level {
+            Some(level) => level,
+            None => return reporter,
+        };
+
+        let source = if self.has_content {
+            let error_chain = expand_via(quote!(error_chain));
+            quote!(let source = #error_chain;)
+        } else {
+            quote!(let source: Option<String> = None;)
+        };
+
+        #[cfg(feature = "log")]
+        let log = {
+            let name = self.name;
+            let log_level = level.expand_log();
+            let name = if self.has_error_id {
+                quote!(format_args!("{} [{}]", #name, error_id))
+            } else {
+                quote!(#name)
+            };
+            Some(quote! {
+                match &source {
+                    Some(source) => {
+                        ::json_resp::__private::log::log!(#log_level, "{} {}", #name, source)
+                    }
+                    None => ::json_resp::__private::log::log!(#log_level, "{}", #name),
+                }
+            })
+        };
+        #[cfg(not(feature = "log"))]
+        let log: Option<TokenStream> = None;
+
+        #[cfg(feature = "tracing")]
+        let tracing = {
+            let name = self.name;
+            let code = self.code;
+            let tracing_level = level.expand_tracing();
+            let message = if self.internal {
+                "internal error"
+            } else {
+                "request error"
+            };
+            let error_id = if self.has_error_id {
+                Some(quote!(error.id = error_id.as_str(),))
+            } else {
+                None
+            };
+            Some(quote! {
+                ::json_resp::__private::tracing::event!(
+                    #tracing_level,
+                    error.code = #code,
+                    http.status = status.as_u16(),
+                    error.variant = #name,
+                    error.source = source.as_deref(),
+                    #error_id
+                    #message
+                );
+            })
+        };
+        #[cfg(not(feature = "tracing"))]
+        let tracing: Option<TokenStream> = None;
+
+        if log.is_none() && tracing.is_none() {
+            return reporter;
+        }
+
+        quote! {
+            #reporter
+            #source
+            #log
+            #tracing
+        }
+    }
+
+    /// Expands to the call to the global `ErrorReporter`
+    fn expand_reporter(&self) -> TokenStream {
+        let type_ident = self.type_ident;
+        let variant = match self.variant {
+            Some(variant) => quote!(Some(stringify!(#variant))),
+            None => quote!(None),
+        };
+        let code = self.code;
+        let internal = self.internal;
+        let error = if self.has_content {
+            expand_via(quote!(as_error))
+        } else {
+            quote!(None)
+        };
+
+        quote! {
+            ::json_resp::__private::report(&::json_resp::reporter::ErrorReport {
+                type_name: stringify!(#type_ident),
+                variant: #variant,
+                code: #code,
+                status,
+                internal: #internal,
+                error: #error,
+            });
+        }
+    }
+}
+
+/// Expands to a call of one of the `Via*` methods on `err`, the most specific implementation is
+/// picked by autoref specialization
+pub(crate) fn expand_via(method: TokenStream) -> TokenStream {
+    quote! {{
+        #[allow(unused_imports)]
+        use ::json_resp::__private::{ViaDisplay, ViaDynError, ViaError, ViaNothing};
+        (&&&&::json_resp::__private::ErrorRef(&err)).#method()
+    }}
+}
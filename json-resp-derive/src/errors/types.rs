@@ -1,7 +1,13 @@
+use super::expand::Config;
+use super::headers::HeaderExpr;
+use super::report::{Level, Report};
 use crate::ctxt::Ctxt;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Attribute, Expr, ExprParen, ExprTuple, Lit, LitInt, LitStr, Path};
+use syn::{
+    Attribute, Expr, ExprParen, ExprTuple, Fields, Lit, LitInt, LitStr, Meta, MetaNameValue, Path,
+    Type,
+};
 use syn::{Ident, Variant};
 
 #[derive(Clone)]
@@ -12,11 +18,29 @@ pub(crate) enum JsonError {
         status: StatusExpr,
         code: LitStr,
         hint: Option<LitStr>,
+        hint_from_display: bool,
         description: Option<LitStr>,
+        /// Doc comments of the variant
+        docs: Option<String>,
+        /// Example of the content shown in the openapi docs
+        example: Option<Expr>,
+        headers: Vec<HeaderExpr>,
+        level: Option<Level>,
     },
     InternalError {
         naive: bool,
         variant: Ident,
+        status: Option<StatusExpr>,
+        code: Option<LitStr>,
+        headers: Vec<HeaderExpr>,
+        level: Option<Level>,
+        /// Types the content is downcasted to, responding with them instead if it succeeds
+        promote: Vec<Path>,
+    },
+    Transparent {
+        variant: Ident,
+        #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+        ty: Type,
     },
 }
 
@@ -25,13 +49,15 @@ impl JsonError {
         match self {
             Self::InternalError { variant, .. } => variant,
             Self::RequestError { variant, .. } => variant,
+            Self::Transparent { variant, .. } => variant,
         }
     }
 
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
     fn status(&self) -> Option<&StatusExpr> {
         match self {
-            Self::InternalError { .. } => None,
             Self::RequestError { status, .. } => Some(status),
+            _ => None,
         }
     }
 
@@ -43,15 +69,7 @@ impl JsonError {
                     continue;
                 }
 
-                return match Self::from_attr(
-                    attr,
-                    variant.fields.is_empty(),
-                    variant.ident.clone(),
-                    ctxt,
-                ) {
-                    Some(attrs) => Some(attrs),
-                    None => return None,
-                };
+                return Self::from_attr(attr, variant, ctxt);
             }
         }
         ctxt.error_spanned_by(
@@ -61,7 +79,7 @@ impl JsonError {
         None
     }
 
-    fn from_attr(attr: &Attribute, naive: bool, variant: Ident, ctxt: &Ctxt) -> Option<Self> {
+    fn from_attr(attr: &Attribute, variant: &Variant, ctxt: &Ctxt) -> Option<Self> {
         let tokens = attr.tokens.to_owned();
 
         let (mode, others) = if let Some(attrs) = extract_mode(tokens) {
@@ -69,24 +87,54 @@ impl JsonError {
         } else {
             ctxt.error_spanned_by(
                 attr.path.clone(),
-                "The first attribute is required and should be either `request` or `internal`",
+                "The first attribute is required and should be either \
+                 `request`, `internal` or `transparent`",
             );
             return None;
         };
 
-        if mode == ErrorType::Internal {
-            return Some(JsonError::InternalError { naive, variant });
+        let naive = variant.fields.is_empty();
+        let ident = variant.ident.clone();
+        let docs = extract_docs(&variant.attrs);
+
+        if mode == ErrorType::Transparent {
+            return Self::from_transparent(attr, variant, others, ctxt);
         }
 
+        let variant = ident;
+
         let mut status: Option<StatusExpr> = None;
         let mut code: Option<LitStr> = None;
         let mut hint: Option<LitStr> = None;
+        let mut hint_from_display = false;
         let mut description: Option<LitStr> = None;
+        let mut example: Option<Expr> = None;
+        let mut headers = Vec::new();
+        let mut level: Option<Level> = None;
+        let mut promote = Vec::new();
 
         let mut wrong_status_or_code = false;
 
         for attr in others {
-            if let Expr::Assign(expr) = attr {
+            if let Expr::Path(path) = &attr {
+                match path.path.get_ident().map(|i| i.to_string()).as_deref() {
+                    Some("hint_from_display") => hint_from_display = true,
+                    _ => ctxt.error_spanned_by(attr, "Unknown flag defined"),
+                }
+            } else if let Expr::Call(call) = &attr {
+                match extract_ident_str(&call.func).as_deref() {
+                    Some("header") => headers.extend(HeaderExpr::from_call(call, naive, ctxt)),
+                    Some("promote") => {
+                        for arg in &call.args {
+                            match arg {
+                                Expr::Path(path) => promote.push(path.path.clone()),
+                                _ => ctxt.error_spanned_by(arg, "promote accepts types only"),
+                            }
+                        }
+                    }
+                    _ => ctxt.error_spanned_by(&call.func, "Unknown attribute defined"),
+                }
+            } else if let Expr::Assign(expr) = attr {
                 let lhs = if let Some(ident_str) = extract_ident_str(&expr.left) {
                     ident_str
                 } else {
@@ -125,6 +173,18 @@ impl JsonError {
                             ctxt.error_spanned_by(expr.right, "hint should be a str");
                         }
                     }
+                    "level" => {
+                        if let Some(val) =
+                            extract_lit_str(&expr.right).and_then(|val| Level::from_lit(&val))
+                        {
+                            level = Some(val);
+                        } else {
+                            ctxt.error_spanned_by(
+                                expr.right,
+                                "level should be one of `error`, `warn`, `info`, `debug` or `trace`",
+                            );
+                        }
+                    }
                     "description" => {
                         if let Some(val) = extract_lit_str(&expr.right) {
                             description = Some(val);
@@ -132,6 +192,7 @@ impl JsonError {
                             ctxt.error_spanned_by(expr.right, "description should be a str");
                         }
                     }
+                    "example" => example = Some(*expr.right),
                     _ => {
                         ctxt.error_spanned_by(expr.left, "Unknown attribute defined");
                     }
@@ -143,14 +204,65 @@ impl JsonError {
                 );
             }
         }
-        if status.is_some() && code.is_some() {
+        if mode == ErrorType::Internal {
+            // Internal errors don't expose any details
+            for (defined, name) in [
+                (hint.is_some(), "hint"),
+                (hint_from_display, "hint_from_display"),
+                (description.is_some(), "description"),
+                (example.is_some(), "example"),
+            ] {
+                if defined {
+                    ctxt.error_spanned_by(
+                        attr,
+                        format!("`{}` can't be used with internal errors", name),
+                    );
+                }
+            }
+
+            if naive && !promote.is_empty() {
+                ctxt.error_spanned_by(attr, "`promote` can only be used on errors with a content");
+            }
+
+            return Some(JsonError::InternalError {
+                naive,
+                variant,
+                status,
+                code,
+                headers,
+                level,
+                promote,
+            });
+        }
+
+        if !promote.is_empty() {
+            ctxt.error_spanned_by(attr, "`promote` can only be used with internal errors");
+        }
+
+        if hint_from_display && hint.is_some() {
+            ctxt.error_spanned_by(
+                attr,
+                "`hint` and `hint_from_display` can't be used together.",
+            );
+        }
+
+        if naive && example.is_some() {
+            ctxt.error_spanned_by(attr, "`example` can only be used on errors with a content");
+        }
+
+        if let (Some(status), Some(code)) = (status, code) {
             Some(JsonError::RequestError {
                 naive,
                 variant,
-                status: status.unwrap(),
-                code: code.unwrap(),
+                status,
+                code,
                 hint,
+                hint_from_display,
                 description,
+                docs,
+                example,
+                headers,
+                level,
             })
         } else {
             if !wrong_status_or_code {
@@ -159,125 +271,291 @@ impl JsonError {
             None
         }
     }
+
+    fn from_transparent(
+        attr: &Attribute,
+        variant: &Variant,
+        others: Vec<Expr>,
+        ctxt: &Ctxt,
+    ) -> Option<Self> {
+        if let Some(other) = others.first() {
+            ctxt.error_spanned_by(other, "`transparent` doesn't accept any other attribute");
+            return None;
+        }
+
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(JsonError::Transparent {
+                variant: variant.ident.clone(),
+                ty: fields.unnamed[0].ty.clone(),
+            }),
+            _ => {
+                ctxt.error_spanned_by(
+                    attr,
+                    "`transparent` can only be used on variants with a single unnamed field",
+                );
+                None
+            }
+        }
+    }
 }
 
 impl JsonError {
+    fn naive(&self) -> bool {
+        match self {
+            Self::RequestError { naive, .. } => *naive,
+            Self::InternalError { naive, .. } => *naive,
+            Self::Transparent { .. } => false,
+        }
+    }
+
+    /// Patterns to match the error with, the first one binds the content to `err` while the second
+    /// one only matches the shape of it
+    ///
+    /// For structs, the content is the struct itself(unless it's a unit struct).
+    fn expand_patterns(&self, type_ident: &Ident, is_struct: bool) -> (TokenStream, TokenStream) {
+        let variant = self.variant();
+        match (is_struct, self) {
+            (true, Self::Transparent { .. }) => (quote!(#type_ident(err)), quote!(#type_ident(..))),
+            (true, _) if self.naive() => (quote!(#type_ident), quote!(#type_ident)),
+            (true, _) => (quote!(err), quote!(_)),
+            (false, _) if self.naive() => {
+                (quote!(#type_ident::#variant), quote!(#type_ident::#variant))
+            }
+            (false, _) => (
+                quote!(#type_ident::#variant(err)),
+                quote!(#type_ident::#variant { .. }),
+            ),
+        }
+    }
+
+    /// Name of the error used in logs
+    fn expand_name(&self, type_ident: &Ident, is_struct: bool) -> TokenStream {
+        let variant = self.variant();
+        if is_struct {
+            quote!(stringify!(#type_ident))
+        } else {
+            quote!(concat!(stringify!(#type_ident), "::", stringify!(#variant)))
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn expand_match_condition(
         &self,
         type_ident: &Ident,
-        internal_error_code: &str,
+        is_struct: bool,
+        config: &Config,
     ) -> TokenStream {
+        let (pattern, shape) = self.expand_patterns(type_ident, is_struct);
+        let name = self.expand_name(type_ident, is_struct);
+
         match &self {
             Self::RequestError {
                 naive,
-                variant,
                 status,
                 code,
                 hint,
+                hint_from_display,
+                headers,
+                level,
                 ..
             } => {
                 let status = status.expand_statuscode();
 
-                let hint = if let Some(hint) = hint {
-                    quote!(Some(String::from(#hint)))
+                let hint = if *hint_from_display {
+                    quote!(.hint(hint))
+                } else if let Some(hint) = hint {
+                    quote!(.hint(String::from(#hint)))
                 } else {
-                    quote!(None)
+                    quote!()
                 };
 
-                if *naive {
-                    quote! {
-                        #type_ident::#variant => ::json_resp::JsonError{
-                            status: #status,
-                            code: #code,
-                            hint: #hint,
-                            content: (),
-                            ..::json_resp::JsonError::default()
-                        }.into_response()
-                    }
-                } else {
-                    quote! {
-                        #type_ident::#variant(err) => ::json_resp::JsonError{
-                            status: #status,
-                            code: #code,
-                            hint: #hint,
-                            content: err,
-                            ..::json_resp::JsonError::default()
-                        }.into_response()
-                    }
+                let content = if *naive { quote!(()) } else { quote!(err) };
+
+                let report = Report {
+                    level: level.or(config.request_level),
+                    internal: false,
+                    name: &name,
+                    type_ident,
+                    variant: (!is_struct).then(|| self.variant()),
+                    code: &quote!(#code),
+                    has_content: !naive,
+                    has_error_id: false,
                 }
-            }
-            Self::InternalError { naive, variant } => {
+                .expand();
+
+                let (headers, headers_call) = HeaderExpr::expand_response_headers(headers);
+
                 let response = quote! {
-                    ::json_resp::JsonError{
-                        status: StatusCode::INTERNAL_SERVER_ERROR,
-                        code: #internal_error_code.into(),
-                        content: (),
-                        ..::json_resp::JsonError::default()
-                    }.into_response()
+                    let status = #status;
+                    #report
+                    #headers
+                    let error = ::json_resp::JsonError::with_content(status, #code, #content)
+                        .error_type(stringify!(#type_ident))
+                        #hint
+                        #headers_call;
+                    ::json_resp::__private::Sink::from_error(error)
                 };
 
-                #[cfg(feature = "log")]
-                let log_error = if *naive {
+                if *hint_from_display {
+                    // The hint has to be rendered before the variant gets destructured
                     quote! {
-                        /// Log the error
-                        ::json_resp::__private::log_error!(
-                            "{}::{}",
-                            stringify!(#type_ident),
-                            stringify!(#variant)
-                        );
+                        __json_resp_self @ #shape => {
+                            let hint = ::std::string::ToString::to_string(&__json_resp_self);
+                            #[allow(irrefutable_let_patterns)]
+                            let #pattern = __json_resp_self else { unreachable!() };
+                            #response
+                        }
                     }
                 } else {
                     quote! {
-                        /// Log the error
-                        ::json_resp::__private::log_error!(
-                            "{}::{} {}",
-                            stringify!(#type_ident),
-                            stringify!(#variant),
-                            err
-                        );
+                        #pattern => {
+                            #response
+                        }
                     }
+                }
+            }
+            Self::Transparent { .. } => {
+                quote! {
+                    #pattern => ::json_resp::__private::Render::render(err)
+                }
+            }
+            Self::InternalError {
+                naive,
+                status,
+                code,
+                headers,
+                level,
+                promote,
+                ..
+            } => {
+                let status = match status {
+                    Some(status) => status.expand_statuscode(),
+                    None => quote!(::json_resp::__private::StatusCode::INTERNAL_SERVER_ERROR),
                 };
-                #[cfg(not(feature = "log"))]
-                let log_error: Option<TokenStream> = None;
 
-                if *naive {
+                // Known errors wrapped in the content(e.g. in an `anyhow::Error`) or in one of its
+                // sources are rendered as they are
+                let promote = promote.iter().map(|ty| {
                     quote! {
-                        #type_ident::#variant => {
-                            #log_error
-                            #response
+                        let err = match err.downcast::<#ty>() {
+                            Ok(err) => return ::json_resp::__private::Render::render(
+                                <_ as ::json_resp::__private::Downcasted<#ty>>::into_inner(err),
+                            ),
+                            Err(err) => err,
+                        };
+                        if let Some(source) = ::json_resp::__private::find_source::<#ty>(
+                            ::core::convert::AsRef::<dyn ::std::error::Error + Send + Sync>::as_ref(&err),
+                        ) {
+                            return ::json_resp::__private::Render::render(source);
                         }
                     }
-                } else {
-                    quote! {
-                        #type_ident::#variant(err) => {
-                            #log_error
-                            #response
-                        }
+                });
+                let code = match code {
+                    Some(code) => code.value(),
+                    None => config.internal_error_code.clone(),
+                };
+
+                // Correlates the response with the logs
+                #[cfg(feature = "error-id")]
+                let (error_id, error_id_call) = (
+                    Some(quote!(let error_id = ::json_resp::__private::error_id();)),
+                    Some(quote!(.error_id(error_id))),
+                );
+                #[cfg(not(feature = "error-id"))]
+                let (error_id, error_id_call): (
+                    Option<TokenStream>,
+                    Option<TokenStream>,
+                ) = (None, None);
+
+                let report = Report {
+                    level: Some(level.unwrap_or(Level::Error)),
+                    internal: true,
+                    name: &name,
+                    type_ident,
+                    variant: (!is_struct).then(|| self.variant()),
+                    code: &quote!(#code),
+                    has_content: !naive,
+                    has_error_id: error_id.is_some(),
+                }
+                .expand();
+
+                let (headers, headers_call) = HeaderExpr::expand_response_headers(headers);
+
+                // Exposes the inner error in development
+                #[cfg(feature = "debug-errors")]
+                let debug = {
+                    let (messages, backtrace) = if *naive {
+                        (quote!(Vec::new()), quote!(None))
+                    } else {
+                        (
+                            super::report::expand_via(quote!(error_messages)),
+                            quote! {{
+                                #[allow(unused_imports)]
+                                use ::json_resp::__private::{ViaBacktrace, ViaNoBacktrace};
+                                (&&::json_resp::__private::ErrorRef(&err)).captured_backtrace()
+                            }},
+                        )
+                    };
+                    Some(quote! {
+                        let error = match ::json_resp::__private::debug_info(#name, || #messages, #backtrace) {
+                            Some(debug) => error.debug(debug),
+                            None => error,
+                        };
+                    })
+                };
+                #[cfg(not(feature = "debug-errors"))]
+                let debug: Option<TokenStream> = None;
+
+                quote! {
+                    #pattern => {
+                        #(#promote)*
+                        let status = #status;
+                        #error_id
+                        #report
+                        #headers
+                        let error = ::json_resp::JsonError::with_content(status, #code, ())
+                            .error_type(stringify!(#type_ident))
+                            #headers_call
+                            #error_id_call;
+                        #debug
+                        ::json_resp::__private::Sink::from_error(error)
                     }
                 }
             }
         }
     }
+}
 
-    fn expand_utoipa_schema_method(&self, name: &Ident) -> Option<TokenStream> {
+/// The openapi docs of the variants
+#[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+impl JsonError {
+    fn expand_utoipa_schema_method(&self, schema_name: &str) -> Option<TokenStream> {
         match self {
             Self::RequestError {
                 naive,
                 status,
                 code,
                 hint,
+                hint_from_display,
+                example,
                 ..
             } => {
                 let status = status.expand_numeric();
 
+                let example = example.as_ref().map(|example| {
+                    quote! {
+                        .example(::json_resp::__private::serde_json::to_value(&#example).ok())
+                    }
+                });
+
                 let content_expand = if *naive {
                     None
                 } else {
                     Some(quote! {
                         .property(
                             "content",
-                            ::json_resp::__private::utoipa::ObjectBuilder::new(),
+                            ::json_resp::__private::utoipa::ObjectBuilder::new()
+                                #example,
                         )
                         .required("content")
                     })
@@ -288,18 +566,27 @@ impl JsonError {
                         .property(
                             "hint",
                             ::json_resp::__private::utoipa::ObjectBuilder::new()
-                                .schema_type(::json_resp::__private::utoipa::SchemaType::Integer)
+                                .schema_type(::json_resp::__private::utoipa::SchemaType::String)
                                 .enum_values(Some([#hint]))
                                 .example(Some(#hint.into())),
                         )
                         .required("hint")
                     }
+                } else if *hint_from_display {
+                    quote! {
+                        .property(
+                            "hint",
+                            ::json_resp::__private::utoipa::ObjectBuilder::new()
+                                .schema_type(::json_resp::__private::utoipa::SchemaType::String),
+                        )
+                        .required("hint")
+                    }
                 } else {
                     quote!()
                 };
 
                 Some(quote! {(
-                    stringify!(#name),
+                    #schema_name,
                     ::json_resp::__private::utoipa::ObjectBuilder::new()
                         .property(
                             "status",
@@ -326,20 +613,25 @@ impl JsonError {
         }
     }
 
-    fn expand_utoipa_response_method(&self, name: &Ident) -> Option<TokenStream> {
+    fn expand_utoipa_response_method(&self, schema_name: &str) -> Option<TokenStream> {
         match self {
             Self::RequestError {
                 description,
+                docs,
                 hint,
                 code,
+                headers,
                 ..
             } => {
-                let description = description
-                    .clone()
-                    .ok_or_else(|| hint.clone())
-                    .unwrap_or_else(|_| code.clone());
+                let description = match (description, docs, hint) {
+                    (Some(description), _, _) => description.value(),
+                    (None, Some(docs), _) => docs.clone(),
+                    (None, None, Some(hint)) => hint.value(),
+                    (None, None, None) => code.value(),
+                };
+                let headers = HeaderExpr::expand_utoipa_headers(headers);
                 Some(quote! {(
-                    stringify!(#name),
+                    #schema_name,
                     ::json_resp::__private::utoipa::ResponseBuilder::new()
                         .description(#description)
                         .content(
@@ -353,6 +645,7 @@ impl JsonError {
                                 .build()
                                 .into(),
                         )
+                        #headers
                         .build()
                         .into(),
                 )})
@@ -375,10 +668,62 @@ impl JsonError {
         })
     }
 
-    pub(crate) fn expand_utoipa_response(&self) -> Option<TokenStream> {
+    /// Names the responses of the inner type of a transparent variant after the variant, so they
+    /// can be used through the outer type's docs module
+    fn expand_utoipa_transparent(name: &Ident, ty: &Type) -> TokenStream {
+        let mut ty = ty.clone();
+
+        // The docs module lives one level deeper than the type, relative paths need one more
+        // `super`
+        if let Type::Path(syn::TypePath { qself: None, path }) = &mut ty {
+            if let Some(first) = path.segments.first_mut() {
+                if path.leading_colon.is_none() && first.ident == "self" {
+                    first.ident = Ident::new("super", first.ident.span());
+                } else if path.leading_colon.is_none() && first.ident == "super" {
+                    let span = first.ident.span();
+                    path.segments.insert(0, Ident::new("super", span).into());
+                }
+            }
+        }
+
+        // Its visibility is capped by the docs module, like the other items of the module
+        quote!(
+            pub type #name = <#ty as ::json_resp::__private::utoipa::ErrorDocs>::All;
+        )
+    }
+
+    /// Path of the type documenting the responses of the error in the docs module, internal errors
+    /// are documented by their groups
+    pub(crate) fn expand_utoipa_responses_path(&self) -> Option<TokenStream> {
+        match self {
+            Self::RequestError { variant, .. } => Some(quote!(#variant)),
+            Self::Transparent { variant, .. } => Some(quote!(#variant)),
+            Self::InternalError { .. } => None,
+        }
+    }
+
+    pub(crate) fn is_named(&self, name: &str) -> bool {
+        self.variant() == name
+    }
+
+    /// The schema and the response are named `{prefix}{variant}`(`{prefix}Error` for structs) to keep
+    /// them apart from the ones of other types
+    pub(crate) fn expand_utoipa_response(
+        &self,
+        prefix: &str,
+        is_struct: bool,
+    ) -> Option<TokenStream> {
+        if let Self::Transparent { variant, ty } = self {
+            return Some(Self::expand_utoipa_transparent(variant, ty));
+        }
+
         let name = self.variant();
-        let schema = self.expand_utoipa_schema_method(&name)?;
-        let response = self.expand_utoipa_response_method(&name)?;
+        let schema_name = match is_struct {
+            true => format!("{}Error", prefix),
+            false => format!("{}{}", prefix, name),
+        };
+        let schema = self.expand_utoipa_schema_method(&schema_name)?;
+        let response = self.expand_utoipa_response_method(&schema_name)?;
         let intoresponse = self.expand_utoipa_intoresponse_method()?;
         Some(quote!(
             pub struct #name;
@@ -404,45 +749,163 @@ impl JsonError {
             }
         ))
     }
+}
 
-    pub(crate) fn expand_utoipa_internal_error(internal_error_code: &str) -> TokenStream {
-        quote!(
-            pub struct InternalError;
+/// Internal errors sharing the same status, they're documented as a single response since they
+/// all look the same to the clients
+#[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+pub(crate) struct InternalErrorGroup {
+    key: String,
+    status: Option<StatusExpr>,
+    codes: Vec<String>,
+    headers: Vec<HeaderExpr>,
+}
+
+#[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+impl InternalErrorGroup {
+    pub(crate) fn from_errors(errors: &[JsonError], internal_error_code: &str) -> Vec<Self> {
+        let mut groups: Vec<Self> = Vec::new();
 
+        for err in errors {
+            let (status, code, headers) = match err {
+                JsonError::InternalError {
+                    status,
+                    code,
+                    headers,
+                    ..
+                } => (status, code, headers),
+                _ => continue,
+            };
 
-            impl ::json_resp::__private::utoipa::ToSchema<'static> for InternalError {
+            let key = match status {
+                Some(status) => status.key(),
+                None => String::from("500"),
+            };
+            let code = match code {
+                Some(code) => code.value(),
+                None => internal_error_code.to_string(),
+            };
+
+            let group = match groups.iter().position(|group| group.key == key) {
+                Some(index) => &mut groups[index],
+                None => {
+                    groups.push(Self {
+                        key,
+                        status: status.clone(),
+                        codes: Vec::new(),
+                        headers: Vec::new(),
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+
+            if !group.codes.contains(&code) {
+                group.codes.push(code);
+            }
+            for header in headers {
+                if !group.headers.iter().any(|h| h.is_same_header(header)) {
+                    group.headers.push(header.clone());
+                }
+            }
+        }
+
+        groups
+    }
+
+    /// `InternalError` for 500 and `InternalError{status}` for the others
+    pub(crate) fn name(&self) -> Ident {
+        let suffix = match self.key.as_str() {
+            "500" => String::new(),
+            key if key.parse::<u16>().is_ok() => key.to_string(),
+            key => key
+                .split('_')
+                .map(|part| {
+                    let mut part = part.to_ascii_lowercase();
+                    if let Some(first) = part.get_mut(0..1) {
+                        first.make_ascii_uppercase();
+                    }
+                    part
+                })
+                .collect(),
+        };
+        Ident::new(
+            &format!("InternalError{}", suffix),
+            proc_macro2::Span::call_site(),
+        )
+    }
+
+    /// The group is described by the docs of the enum, internal variants keep their docs private
+    pub(crate) fn expand_utoipa_internal_error(
+        &self,
+        prefix: &str,
+        description: Option<&str>,
+    ) -> TokenStream {
+        let name = self.name();
+        let description = description.unwrap_or("InternalError");
+        let schema_name = format!("{}{}", prefix, name);
+        let status = match &self.status {
+            Some(status) => status.expand_numeric(),
+            None => quote!(500u16),
+        };
+        let codes = &self.codes;
+        let example = &self.codes[0];
+        let headers = HeaderExpr::expand_utoipa_headers(&self.headers);
+
+        #[cfg(feature = "error-id")]
+        let meta = Some(quote! {
+            .property(
+                "meta",
+                ::json_resp::__private::utoipa::ObjectBuilder::new()
+                    .property(
+                        "error_id",
+                        ::json_resp::__private::utoipa::ObjectBuilder::new()
+                            .schema_type(::json_resp::__private::utoipa::SchemaType::String)
+                            .description(Some("Id used to correlate the error with the logs")),
+                    )
+                    .required("error_id"),
+            )
+            .required("meta")
+        });
+        #[cfg(not(feature = "error-id"))]
+        let meta: Option<TokenStream> = None;
+
+        quote!(
+            pub struct #name;
+
+            impl ::json_resp::__private::utoipa::ToSchema<'static> for #name {
                 fn schema() -> (&'static str, ::json_resp::__private::utoipa::RefOr<::json_resp::__private::utoipa::Schema>) {
                     (
-                        "InternalError",
+                        #schema_name,
                         ::json_resp::__private::utoipa::ObjectBuilder::new()
                             .property(
                                 "status",
                                 ::json_resp::__private::utoipa::ObjectBuilder::new()
                                     .schema_type(::json_resp::__private::utoipa::SchemaType::Integer)
-                                    .enum_values(Some(["500"]))
-                                    .example(Some("500".into())),
+                                    .enum_values(Some([#status]))
+                                    .example(Some(#status.into())),
                             )
                             .required("status")
                             .property(
                                 "code",
                                 ::json_resp::__private::utoipa::ObjectBuilder::new()
                                     .schema_type(::json_resp::__private::utoipa::SchemaType::String)
-                                    .enum_values(Some([#internal_error_code]))
-                                    .example(Some(#internal_error_code.into())),
+                                    .enum_values(Some([#(#codes),*]))
+                                    .example(Some(#example.into())),
                             )
                             .required("code")
+                            #meta
                             .build()
                             .into(),
                     )
                 }
             }
 
-            impl ::json_resp::__private::utoipa::ToResponse<'static> for InternalError {
+            impl ::json_resp::__private::utoipa::ToResponse<'static> for #name {
                 fn response() -> (&'static str, ::json_resp::__private::utoipa::RefOr<::json_resp::__private::utoipa::Response>) {
                     (
-                        "InternalError",
+                        #schema_name,
                         ::json_resp::__private::utoipa::ResponseBuilder::new()
-                            .description("InternalError")
+                            .description(#description)
                             .content(
                                 "application/json",
                                 ::json_resp::__private::utoipa::ContentBuilder::new()
@@ -454,20 +917,21 @@ impl JsonError {
                                     .build()
                                     .into(),
                             )
+                            #headers
                             .build()
                             .into()
                         )
                 }
             }
 
-            impl ::json_resp::__private::utoipa::IntoResponses for InternalError {
+            impl ::json_resp::__private::utoipa::IntoResponses for #name {
                 fn responses() -> std::collections::BTreeMap<
                     String,
                     ::json_resp::__private::utoipa::RefOr<::json_resp::__private::utoipa::Response>,
                 > {
                     ::json_resp::__private::utoipa::ResponsesBuilder::new()
                         .response(
-                            "500",
+                            #status.to_string(),
                             <Self as ::json_resp::__private::utoipa::ToResponse>::response().1,
                         )
                         .build()
@@ -482,6 +946,7 @@ impl JsonError {
 enum ErrorType {
     Request,
     Internal,
+    Transparent,
 }
 
 #[derive(Clone)]
@@ -497,15 +962,36 @@ impl StatusExpr {
                 quote! (#p)
             }
             Self::Lit(n) => {
-                quote!(StatusCode::from_u16(#n).unwrap())
+                quote!(::json_resp::__private::StatusCode::from_u16(#n).unwrap())
+            }
+        }
+    }
+
+    /// A key identifying the status at compile time, the numeric code when it's known, otherwise
+    /// the name of the constant
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+    pub fn key(&self) -> String {
+        match self {
+            Self::Lit(n) => n.base10_digits().to_string(),
+            Self::Path(p) => {
+                let name = p
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string())
+                    .unwrap_or_default();
+                match status_code_of(&name) {
+                    Some(code) => code.to_string(),
+                    None => name,
+                }
             }
         }
     }
 
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
     pub fn expand_numeric(&self) -> TokenStream {
         match self {
             Self::Path(p) => {
-                quote!(StatusCode::as_u16(&#p))
+                quote!(::json_resp::__private::StatusCode::as_u16(&#p))
             }
             Self::Lit(n) => {
                 quote! (#n)
@@ -514,19 +1000,108 @@ impl StatusExpr {
     }
 }
 
-fn extract_lit_str(expr: &Expr) -> Option<LitStr> {
-    match expr {
-        Expr::Lit(lit) => {
-            if let Lit::Str(lit) = &lit.lit {
-                return Some(lit.clone());
-            }
+/// Numeric code of `http::StatusCode` constants
+#[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+fn status_code_of(name: &str) -> Option<u16> {
+    let code = match name {
+        "CONTINUE" => 100,
+        "SWITCHING_PROTOCOLS" => 101,
+        "PROCESSING" => 102,
+        "OK" => 200,
+        "CREATED" => 201,
+        "ACCEPTED" => 202,
+        "NON_AUTHORITATIVE_INFORMATION" => 203,
+        "NO_CONTENT" => 204,
+        "RESET_CONTENT" => 205,
+        "PARTIAL_CONTENT" => 206,
+        "MULTI_STATUS" => 207,
+        "ALREADY_REPORTED" => 208,
+        "IM_USED" => 226,
+        "MULTIPLE_CHOICES" => 300,
+        "MOVED_PERMANENTLY" => 301,
+        "FOUND" => 302,
+        "SEE_OTHER" => 303,
+        "NOT_MODIFIED" => 304,
+        "USE_PROXY" => 305,
+        "TEMPORARY_REDIRECT" => 307,
+        "PERMANENT_REDIRECT" => 308,
+        "BAD_REQUEST" => 400,
+        "UNAUTHORIZED" => 401,
+        "PAYMENT_REQUIRED" => 402,
+        "FORBIDDEN" => 403,
+        "NOT_FOUND" => 404,
+        "METHOD_NOT_ALLOWED" => 405,
+        "NOT_ACCEPTABLE" => 406,
+        "PROXY_AUTHENTICATION_REQUIRED" => 407,
+        "REQUEST_TIMEOUT" => 408,
+        "CONFLICT" => 409,
+        "GONE" => 410,
+        "LENGTH_REQUIRED" => 411,
+        "PRECONDITION_FAILED" => 412,
+        "PAYLOAD_TOO_LARGE" => 413,
+        "URI_TOO_LONG" => 414,
+        "UNSUPPORTED_MEDIA_TYPE" => 415,
+        "RANGE_NOT_SATISFIABLE" => 416,
+        "EXPECTATION_FAILED" => 417,
+        "IM_A_TEAPOT" => 418,
+        "MISDIRECTED_REQUEST" => 421,
+        "UNPROCESSABLE_ENTITY" => 422,
+        "LOCKED" => 423,
+        "FAILED_DEPENDENCY" => 424,
+        "UPGRADE_REQUIRED" => 426,
+        "PRECONDITION_REQUIRED" => 428,
+        "TOO_MANY_REQUESTS" => 429,
+        "REQUEST_HEADER_FIELDS_TOO_LARGE" => 431,
+        "UNAVAILABLE_FOR_LEGAL_REASONS" => 451,
+        "INTERNAL_SERVER_ERROR" => 500,
+        "NOT_IMPLEMENTED" => 501,
+        "BAD_GATEWAY" => 502,
+        "SERVICE_UNAVAILABLE" => 503,
+        "GATEWAY_TIMEOUT" => 504,
+        "HTTP_VERSION_NOT_SUPPORTED" => 505,
+        "VARIANT_ALSO_NEGOTIATES" => 506,
+        "INSUFFICIENT_STORAGE" => 507,
+        "LOOP_DETECTED" => 508,
+        "NOT_EXTENDED" => 510,
+        "NETWORK_AUTHENTICATION_REQUIRED" => 511,
+        _ => return None,
+    };
+    Some(code)
+}
+
+pub(crate) fn extract_lit_str(expr: &Expr) -> Option<LitStr> {
+    if let Expr::Lit(lit) = expr {
+        if let Lit::Str(lit) = &lit.lit {
+            return Some(lit.clone());
         }
-        _ => {}
     }
     None
 }
 
-fn extract_status(expr: &Expr) -> Option<StatusExpr> {
+/// Doc comments of an item, `None` if it has none
+pub(crate) fn extract_docs(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(lit), ..
+            })) => Some(lit.value()),
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let docs = lines.join("\n").trim().to_string();
+    (!docs.is_empty()).then_some(docs)
+}
+
+pub(crate) fn extract_status(expr: &Expr) -> Option<StatusExpr> {
     match expr {
         Expr::Lit(lit) => {
             if let Lit::Int(lit) = &lit.lit {
@@ -539,7 +1114,7 @@ fn extract_status(expr: &Expr) -> Option<StatusExpr> {
     None
 }
 
-fn extract_ident_str(expr: &Expr) -> Option<String> {
+pub(crate) fn extract_ident_str(expr: &Expr) -> Option<String> {
     if let Expr::Path(path) = expr {
         Some(path.path.get_ident()?.to_string())
     } else {
@@ -552,10 +1127,11 @@ fn extract_type(attr: &Expr) -> Option<ErrorType> {
         match path.path.get_ident()?.to_string().as_str() {
             "request" => Some(ErrorType::Request),
             "internal" => Some(ErrorType::Internal),
-            _ => return None,
+            "transparent" => Some(ErrorType::Transparent),
+            _ => None,
         }
     } else {
-        return None;
+        None
     }
 }
 
@@ -2,6 +2,7 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 mod ctxt;
 mod errors;
+mod success;
 use quote::quote;
 
 #[proc_macro_derive(JsonError, attributes(json_error))]
@@ -13,6 +14,15 @@ pub fn derive_api_error(input: TokenStream) -> TokenStream {
         .into()
 }
 
+#[proc_macro_derive(JsonSuccess, attributes(json_success))]
+pub fn derive_json_success(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    success::expand_derive(&input)
+        .unwrap_or_else(to_compile_errors)
+        .into()
+}
+
 fn to_compile_errors(errors: Vec<syn::Error>) -> proc_macro2::TokenStream {
     let compile_errors = errors.iter().map(syn::Error::to_compile_error);
     quote!(#(#compile_errors)*)
@@ -0,0 +1,259 @@
+use crate::ctxt::Ctxt;
+use crate::errors::{
+    extract_docs, extract_exprs, extract_ident_str, extract_lit_str, extract_status, StatusExpr,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Data, DataEnum, Expr, Fields, Ident, LitInt, LitStr, Type, Variant};
+
+pub fn expand_derive(input: &syn::DeriveInput) -> Result<TokenStream, Vec<syn::Error>> {
+    let ctxt = Ctxt::new();
+
+    let successes = match &input.data {
+        Data::Enum(DataEnum { variants, .. }) => variants
+            .iter()
+            .filter_map(|variant| JsonSuccess::from_variant(variant, &ctxt))
+            .collect(),
+        _ => {
+            ctxt.error_spanned_by(input, "Expected `enum`");
+            Vec::new()
+        }
+    };
+    ctxt.check()?;
+
+    Ok(expand_json_successes(input, &successes))
+}
+
+fn expand_json_successes(input: &syn::DeriveInput, successes: &[JsonSuccess]) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let arms = successes
+        .iter()
+        .map(|success| success.expand_match_arm(name));
+    let gen = quote! {
+        impl #impl_generics ::json_resp::__private::IntoResponse for #name #ty_generics #where_clause {
+            fn into_response(self) -> ::json_resp::__private::Response {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    };
+
+    #[cfg(feature = "openapi")]
+    let gen = {
+        let responses = StatusGroup::from_successes(successes)
+            .into_iter()
+            .map(|group| group.expand_utoipa_response());
+
+        quote! {
+            #gen
+
+            impl #impl_generics ::json_resp::__private::utoipa::IntoResponses for #name #ty_generics #where_clause {
+                fn responses() -> std::collections::BTreeMap<
+                    String,
+                    ::json_resp::__private::utoipa::RefOr<::json_resp::__private::utoipa::Response>,
+                > {
+                    ::json_resp::__private::utoipa::ResponsesBuilder::new()
+                        #(#responses)*
+                        .build()
+                        .into()
+                }
+            }
+        }
+    };
+
+    gen
+}
+
+/// A variant of the enum, responded as a `JsonResponse` with its own status
+pub(crate) struct JsonSuccess {
+    variant: Ident,
+    /// Type of the content, unit variants respond without a content
+    ty: Option<Type>,
+    /// Type of the meta, taken from the second field of the variant
+    meta: Option<Type>,
+    status: StatusExpr,
+    /// Taken from the `description` attribute or the doc comments of the variant
+    #[cfg_attr(not(feature = "openapi"), allow(dead_code))]
+    description: Option<String>,
+}
+
+impl JsonSuccess {
+    /// Variants without an attribute respond with `200`
+    fn from_variant(variant: &Variant, ctxt: &Ctxt) -> Option<Self> {
+        let (ty, meta) = match &variant.fields {
+            Fields::Unit => (None, None),
+            Fields::Unnamed(fields) if fields.unnamed.len() <= 2 => {
+                let mut types = fields.unnamed.iter().map(|field| field.ty.clone());
+                (types.next(), types.next())
+            }
+            _ => {
+                ctxt.error_spanned_by(
+                    &variant.ident,
+                    "Variants should either be unit or have a content and an optional meta as \
+                     unnamed fields",
+                );
+                return None;
+            }
+        };
+
+        let mut status = None;
+        let mut description = None;
+
+        let exprs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("json_success"))
+            .flat_map(|attr| extract_exprs(attr.tokens.clone()));
+
+        for expr in exprs {
+            let assign = match expr {
+                Expr::Assign(assign) => assign,
+                expr => {
+                    ctxt.error_spanned_by(
+                        expr,
+                        "Only assignments are allowed to be used in success attributes.",
+                    );
+                    continue;
+                }
+            };
+
+            match extract_ident_str(&assign.left).as_deref() {
+                Some("status") => match extract_status(&assign.right) {
+                    Some(val) => status = Some(val),
+                    None => ctxt.error_spanned_by(
+                        assign.right,
+                        "status should be either a number or a path(StatusCode::CREATED)",
+                    ),
+                },
+                Some("description") => match extract_lit_str(&assign.right) {
+                    Some(val) => description = Some(val),
+                    None => ctxt.error_spanned_by(assign.right, "description should be a str"),
+                },
+                _ => ctxt.error_spanned_by(assign.left, "Unknown attribute defined"),
+            }
+        }
+
+        Some(Self {
+            variant: variant.ident.clone(),
+            ty,
+            meta,
+            status: status
+                .unwrap_or_else(|| StatusExpr::Lit(LitInt::new("200", Span::call_site()))),
+            description: description
+                .map(|description: LitStr| description.value())
+                .or_else(|| extract_docs(&variant.attrs)),
+        })
+    }
+
+    fn expand_match_arm(&self, type_ident: &Ident) -> TokenStream {
+        let variant = &self.variant;
+        let status = self.status.expand_statuscode();
+
+        let (pattern, content, meta) = match (&self.ty, &self.meta) {
+            (Some(_), Some(_)) => (
+                quote!(#type_ident::#variant(content, meta)),
+                quote!(content),
+                quote!(meta),
+            ),
+            (Some(_), None) => (
+                quote!(#type_ident::#variant(content)),
+                quote!(content),
+                quote!(::json_resp::Nothing),
+            ),
+            _ => (
+                quote!(#type_ident::#variant),
+                quote!(::json_resp::Nothing),
+                quote!(::json_resp::Nothing),
+            ),
+        };
+
+        quote! {
+            #pattern => ::json_resp::__private::IntoResponse::into_response(
+                ::json_resp::JsonResponse {
+                    status: #status,
+                    content: #content,
+                    meta: #meta,
+                }
+            )
+        }
+    }
+}
+
+/// Variants sharing the same status, documented as a single response with either of their
+/// schemas
+#[cfg(feature = "openapi")]
+struct StatusGroup<'a> {
+    key: String,
+    successes: Vec<&'a JsonSuccess>,
+}
+
+#[cfg(feature = "openapi")]
+impl<'a> StatusGroup<'a> {
+    fn from_successes(successes: &'a [JsonSuccess]) -> Vec<Self> {
+        let mut groups: Vec<Self> = Vec::new();
+        for success in successes {
+            let key = success.status.key();
+            match groups.iter_mut().find(|group| group.key == key) {
+                Some(group) => group.successes.push(success),
+                None => groups.push(Self {
+                    key,
+                    successes: vec![success],
+                }),
+            }
+        }
+        groups
+    }
+
+    fn expand_utoipa_response(&self) -> TokenStream {
+        let status = self.successes[0].status.expand_numeric();
+
+        let description = self
+            .successes
+            .iter()
+            .map(|success| match &success.description {
+                Some(description) => description.clone(),
+                None => success.variant.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        let schemas = self.successes.iter().map(|success| {
+            let content = match &success.ty {
+                Some(ty) => quote!(#ty),
+                None => quote!(::json_resp::Nothing),
+            };
+            let meta = match &success.meta {
+                Some(meta) => quote!(#meta),
+                None => quote!(::json_resp::Nothing),
+            };
+            quote!(::json_resp::__private::utoipa::json_response_schema::<#content, #meta>(#status))
+        });
+
+        let schema = if self.successes.len() == 1 {
+            quote!(#(#schemas)*)
+        } else {
+            quote! {
+                ::json_resp::__private::utoipa::OneOfBuilder::new()
+                    #(.item(#schemas))*
+            }
+        };
+
+        quote! {
+            .response(
+                #status.to_string(),
+                ::json_resp::__private::utoipa::ResponseBuilder::new()
+                    .description(#description)
+                    .content(
+                        "application/json",
+                        ::json_resp::__private::utoipa::ContentBuilder::new()
+                            .schema(#schema)
+                            .build(),
+                    )
+                    .build(),
+            )
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use axum::http::StatusCode;
+
+use crate::{
+    render::{Render, Sink},
+    JsonError,
+};
+
+/// Converts the error into an internal error, logging/reporting its whole chain
+impl From<anyhow::Error> for JsonError {
+    fn from(err: anyhow::Error) -> Self {
+        crate::internal::internal_error(
+            "anyhow::Error",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal-error",
+            &*err,
+            Some(err.backtrace()),
+        )
+    }
+}
+
+impl Render for anyhow::Error {
+    fn render<S: Sink>(self) -> S {
+        S::from_error(JsonError::from(self))
+    }
+}
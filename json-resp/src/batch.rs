@@ -0,0 +1,185 @@
+use std::marker::PhantomData;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{response::as_u16, DynJsonError, Envelope, JsonError, Nothing};
+
+/// Summary of a batch, used as the `meta` of [`JsonBatchResponse`]
+#[derive(Debug, Default, Serialize)]
+pub struct JsonBatchMeta {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum BatchResult<T> {
+    Success {
+        #[serde(serialize_with = "as_u16")]
+        status: StatusCode,
+        content: T,
+    },
+    Failure(Box<JsonError<Value>>),
+}
+
+/// The result of a single item of a batch, either a success or an error with its own status
+#[derive(Debug, Serialize)]
+pub struct BatchEntry<T> {
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(flatten)]
+    result: BatchResult<T>,
+}
+
+impl<T> BatchEntry<T> {
+    /// A successful item responded with `200`
+    pub fn success(content: T) -> Self {
+        Self {
+            index: 0,
+            id: None,
+            result: BatchResult::Success {
+                status: StatusCode::OK,
+                content,
+            },
+        }
+    }
+
+    pub fn failure<E: Into<DynJsonError>>(error: E) -> Self {
+        Self {
+            index: 0,
+            id: None,
+            result: BatchResult::Failure(Box::new(error.into().into_inner())),
+        }
+    }
+
+    /// Set the status of a successful item
+    pub fn status(mut self, status: StatusCode) -> Self {
+        if let BatchResult::Success { status: old, .. } = &mut self.result {
+            *old = status;
+        }
+        self
+    }
+
+    /// Identify the item with something other than its index
+    pub fn id<I: Into<String>>(mut self, id: I) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self.result, BatchResult::Success { .. })
+    }
+}
+
+impl<T, E: Into<DynJsonError>> From<Result<T, E>> for BatchEntry<T> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(content) => Self::success(content),
+            Err(error) => Self::failure(error),
+        }
+    }
+}
+
+/// A `207` response for bulk operations, where each item succeeds or fails independently
+///
+/// ```json5
+/// {
+///     "status": 207,
+///     "content": [
+///         {"index": 0, "status": 201, "content": T},
+///         {"index": 1, "id": "b", "status": 409, "code": "already-exists", "content": null}
+///     ],
+///     "meta": {"total": 2, "succeeded": 1, "failed": 1}
+/// }
+/// ```
+///
+/// `E` only documents the errors of the items in openapi(a derived error, e.g. `MyAppErrors`), it
+/// doesn't restrict the errors which can be pushed.
+#[derive(Debug)]
+pub struct JsonBatchResponse<T, E = Nothing> {
+    entries: Vec<BatchEntry<T>>,
+    errors: PhantomData<fn() -> E>,
+}
+
+impl<T, E> Default for JsonBatchResponse<T, E> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            errors: PhantomData,
+        }
+    }
+}
+
+impl<T, E> JsonBatchResponse<T, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the result of the next item, it gets the next index
+    pub fn push<B: Into<BatchEntry<T>>>(&mut self, entry: B) {
+        let mut entry = entry.into();
+        entry.index = self.entries.len();
+        self.entries.push(entry);
+    }
+
+    pub fn entry<B: Into<BatchEntry<T>>>(mut self, entry: B) -> Self {
+        self.push(entry);
+        self
+    }
+
+    pub fn meta(&self) -> JsonBatchMeta {
+        let succeeded = self
+            .entries
+            .iter()
+            .filter(|entry| entry.is_success())
+            .count();
+        JsonBatchMeta {
+            total: self.entries.len(),
+            succeeded,
+            failed: self.entries.len() - succeeded,
+        }
+    }
+}
+
+impl<T, E, B: Into<BatchEntry<T>>> FromIterator<B> for JsonBatchResponse<T, E> {
+    fn from_iter<I: IntoIterator<Item = B>>(iter: I) -> Self {
+        let mut batch = Self::new();
+        for entry in iter {
+            batch.push(entry);
+        }
+        batch
+    }
+}
+
+#[derive(Serialize)]
+struct Body<'a, T> {
+    #[serde(serialize_with = "as_u16")]
+    status: StatusCode,
+    content: &'a [BatchEntry<T>],
+    meta: JsonBatchMeta,
+}
+
+impl<T: Serialize, E> IntoResponse for JsonBatchResponse<T, E> {
+    fn into_response(self) -> Response {
+        let status = StatusCode::MULTI_STATUS;
+        let mut response = (
+            status,
+            Json(Body {
+                status,
+                content: &self.entries,
+                meta: self.meta(),
+            }),
+        )
+            .into_response();
+        response.extensions_mut().insert(Envelope);
+        response
+    }
+}
@@ -0,0 +1,63 @@
+//! Exposing the details of internal errors while developing.
+//!
+//! When enabled, responses of internal errors include the inner error, its sources and the
+//! backtrace captured by the error(only `anyhow::Error` captures one, when `RUST_BACKTRACE` or
+//! `RUST_LIB_BACKTRACE` is set) in their `debug` field. It's enabled by default in debug
+//! builds and disabled in release builds, unless explicitly changed with [`enable`]/[`disable`].
+
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use crate::JsonErrorDebug;
+
+const UNSET: u8 = 0;
+const ENABLED: u8 = 1;
+const DISABLED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Include the details of internal errors in the responses
+pub fn enable() {
+    STATE.store(ENABLED, Ordering::Relaxed);
+}
+
+/// Hide the details of internal errors from the responses
+pub fn disable() {
+    STATE.store(DISABLED, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        ENABLED => true,
+        DISABLED => false,
+        _ => cfg!(debug_assertions),
+    }
+}
+
+/// Build the debug details of an error out of its messages(the error itself followed by its
+/// sources) and the backtrace it captured, `None` if the debug mode is disabled
+#[doc(hidden)]
+pub fn debug_info<F>(
+    name: &str,
+    messages: F,
+    backtrace: Option<&Backtrace>,
+) -> Option<JsonErrorDebug>
+where
+    F: FnOnce() -> Vec<String>,
+{
+    if !is_enabled() {
+        return None;
+    }
+
+    let mut messages = messages().into_iter();
+
+    Some(JsonErrorDebug {
+        error: messages.next().unwrap_or_else(|| name.to_string()),
+        sources: messages.collect(),
+        backtrace: backtrace
+            .filter(|backtrace| backtrace.status() == BacktraceStatus::Captured)
+            .map(|backtrace| backtrace.to_string()),
+    })
+}
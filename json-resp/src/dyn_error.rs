@@ -0,0 +1,73 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{render::Render, JsonError};
+
+/// A `JsonError` with its content erased, any `JsonError` or derived error can be converted into
+/// it, so handlers can use `?` on different error types
+#[derive(Debug)]
+pub struct DynJsonError(JsonError<Value>);
+
+impl DynJsonError {
+    /// A content which fails to serialize is replaced by `null`, the rest of the error is kept
+    pub fn new<T: Serialize>(error: JsonError<T>) -> Self {
+        let content = match serde_json::to_value(&error.content) {
+            Ok(content) => content,
+            #[allow(unused_variables)]
+            Err(err) => {
+                #[cfg(feature = "log")]
+                log::error!("failed to serialize the content of {}: {}", error.code, err);
+                #[cfg(feature = "tracing")]
+                tracing::error!(
+                    error.code = error.code,
+                    error.source = %err,
+                    "failed to serialize the error content"
+                );
+                Value::Null
+            }
+        };
+        Self(error.content(content))
+    }
+
+    pub fn into_inner(self) -> JsonError<Value> {
+        self.0
+    }
+}
+
+impl<E: Render> From<E> for DynJsonError {
+    fn from(error: E) -> Self {
+        error.render()
+    }
+}
+
+impl Deref for DynJsonError {
+    type Target = JsonError<Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for DynJsonError {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl fmt::Display for DynJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl IntoResponse for DynJsonError {
+    fn into_response(self) -> Response {
+        self.0.into_response()
+    }
+}
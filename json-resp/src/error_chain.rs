@@ -0,0 +1,191 @@
+//! Helpers used by the derive macro to format inner errors.
+//!
+//! Variants of a derived enum may hold anything, but when the inner value implements
+//! `std::error::Error` we want to walk its `source()` chain and when it only implements `Display`
+//! we still want to show it. This is done with autoref-based specialization, the generated code
+//! calls `(&&&&ErrorRef(&err)).error_chain()` and the compiler picks the most specific
+//! implementation available: a pointer to `dyn Error`(`BoxError`, `anyhow::Error`, ...), then
+//! `Error`, then `Display`, then nothing. Backtraces are found the same way with
+//! `(&&ErrorRef(&err)).captured_backtrace()`.
+
+use std::{backtrace::Backtrace, error::Error, fmt, ops::Deref};
+
+pub struct ErrorRef<'a, T: ?Sized>(pub &'a T);
+
+/// Formats an error and all of its sources separated by `": "`
+pub struct Chain<'a>(pub(crate) &'a dyn Error);
+
+impl<'a> fmt::Display for Chain<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)?;
+        let mut source = self.0.source();
+        while let Some(err) = source {
+            write!(f, ": {}", err)?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn messages(error: &dyn Error) -> Vec<String> {
+    let mut messages = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(err) = source {
+        messages.push(err.to_string());
+        source = err.source();
+    }
+    messages
+}
+
+/// Find an error of type `T` among the sources of `error`, used by `promote` to respond with known
+/// errors wrapped in other errors
+pub fn find_source<T>(error: &(dyn Error + 'static)) -> Option<T>
+where
+    T: Error + Clone + 'static,
+{
+    let mut source = error.source();
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<T>() {
+            return Some(err.clone());
+        }
+        source = err.source();
+    }
+    None
+}
+
+/// The result of a downcast by `promote`, `anyhow::Error` and `eyre::Report` give the error itself
+/// while `Box<dyn Error>` gives a `Box` of it
+pub trait Downcasted<T> {
+    fn into_inner(self) -> T;
+}
+
+impl<T> Downcasted<T> for T {
+    fn into_inner(self) -> T {
+        self
+    }
+}
+
+impl<T> Downcasted<T> for Box<T> {
+    fn into_inner(self) -> T {
+        *self
+    }
+}
+
+pub trait ViaDynError {
+    fn error_chain(&self) -> Option<String>;
+
+    fn error_messages(&self) -> Vec<String>;
+
+    fn as_error(&self) -> Option<&dyn Error>;
+}
+
+impl<'a, T> ViaDynError for &&&ErrorRef<'a, T>
+where
+    T: Deref<Target = dyn Error + Send + Sync>,
+{
+    fn error_chain(&self) -> Option<String> {
+        Some(Chain(&**self.0).to_string())
+    }
+
+    fn error_messages(&self) -> Vec<String> {
+        messages(&**self.0)
+    }
+
+    fn as_error(&self) -> Option<&dyn Error> {
+        Some(&**self.0)
+    }
+}
+
+pub trait ViaError {
+    fn error_chain(&self) -> Option<String>;
+
+    fn error_messages(&self) -> Vec<String>;
+
+    fn as_error(&self) -> Option<&dyn Error>;
+}
+
+impl<'a, T> ViaError for &&ErrorRef<'a, T>
+where
+    T: Error,
+{
+    fn error_chain(&self) -> Option<String> {
+        Some(Chain(self.0).to_string())
+    }
+
+    fn error_messages(&self) -> Vec<String> {
+        messages(self.0)
+    }
+
+    fn as_error(&self) -> Option<&dyn Error> {
+        Some(self.0)
+    }
+}
+
+pub trait ViaDisplay {
+    fn error_chain(&self) -> Option<String>;
+
+    fn error_messages(&self) -> Vec<String>;
+
+    fn as_error(&self) -> Option<&dyn Error>;
+}
+
+impl<'a, T> ViaDisplay for &ErrorRef<'a, T>
+where
+    T: fmt::Display + ?Sized,
+{
+    fn error_chain(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn error_messages(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+
+    fn as_error(&self) -> Option<&dyn Error> {
+        None
+    }
+}
+
+pub trait ViaNothing {
+    fn error_chain(&self) -> Option<String>;
+
+    fn error_messages(&self) -> Vec<String>;
+
+    fn as_error(&self) -> Option<&dyn Error>;
+}
+
+impl<'a, T: ?Sized> ViaNothing for ErrorRef<'a, T> {
+    fn error_chain(&self) -> Option<String> {
+        None
+    }
+
+    fn error_messages(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn as_error(&self) -> Option<&dyn Error> {
+        None
+    }
+}
+
+/// The backtrace captured by the error itself, only `anyhow::Error` exposes one on stable
+pub trait ViaBacktrace {
+    fn captured_backtrace(&self) -> Option<&Backtrace>;
+}
+
+#[cfg(feature = "anyhow")]
+impl<'a> ViaBacktrace for &ErrorRef<'a, anyhow::Error> {
+    fn captured_backtrace(&self) -> Option<&Backtrace> {
+        Some(self.0.backtrace())
+    }
+}
+
+pub trait ViaNoBacktrace {
+    fn captured_backtrace(&self) -> Option<&Backtrace>;
+}
+
+impl<'a, T: ?Sized> ViaNoBacktrace for ErrorRef<'a, T> {
+    fn captured_backtrace(&self) -> Option<&Backtrace> {
+        None
+    }
+}
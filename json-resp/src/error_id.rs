@@ -0,0 +1,99 @@
+//! Correlation ids for internal errors.
+//!
+//! Internal errors hide their details from the clients, so each one of them gets an id which is
+//! logged alongside the inner error and returned in the `meta.error_id` field of the response.
+//!
+//! By default a new id is generated for every error, wrapping the router with [`ErrorIdLayer`]
+//! makes all the errors of a request use the incoming `X-Request-Id` header instead(or a single
+//! generated id if the header is missing).
+
+use std::task::{Context, Poll};
+
+use axum::http::{HeaderName, Request};
+use tokio::task::futures::TaskLocalFuture;
+use tower_layer::Layer;
+use tower_service::Service;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Generate a new error id
+pub fn generate() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The id of the request being handled if any, otherwise a newly generated id
+pub fn current() -> String {
+    REQUEST_ID
+        .try_with(|id| id.clone())
+        .unwrap_or_else(|_| generate())
+}
+
+/// A layer making internal errors use the incoming request id as their error id
+#[derive(Debug, Clone)]
+pub struct ErrorIdLayer {
+    header: HeaderName,
+}
+
+impl ErrorIdLayer {
+    pub fn new() -> Self {
+        Self {
+            header: HeaderName::from_static("x-request-id"),
+        }
+    }
+
+    /// Read the request id from another header
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+}
+
+impl Default for ErrorIdLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for ErrorIdLayer {
+    type Service = ErrorId<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ErrorId {
+            inner,
+            header: self.header.clone(),
+        }
+    }
+}
+
+/// The service created by [`ErrorIdLayer`]
+#[derive(Debug, Clone)]
+pub struct ErrorId<S> {
+    inner: S,
+    header: HeaderName,
+}
+
+impl<S, B> Service<Request<B>> for ErrorId<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<String, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let id = req
+            .headers()
+            .get(&self.header)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+            .unwrap_or_else(generate);
+
+        REQUEST_ID.scope(id, self.inner.call(req))
+    }
+}
@@ -0,0 +1,68 @@
+//! Shorthands for converting `Result`s and `Option`s into `JsonError`s.
+
+use std::error::Error;
+
+use axum::http::StatusCode;
+
+use crate::JsonError;
+
+// `JsonError` is big, but it's meant to be returned from the handlers as is
+#[allow(clippy::result_large_err)]
+pub trait ResultExt<T, E> {
+    /// Replace the error with a `JsonError`
+    fn json_err(self, status: StatusCode, code: &'static str) -> Result<T, JsonError>;
+
+    /// Replace the error with a `404` error
+    fn or_not_found(self, code: &'static str) -> Result<T, JsonError>;
+
+    /// Replace the error with an internal error, the original error is logged/traced and reported
+    /// like the internal errors of the derive. Anything convertible into a boxed error is accepted,
+    /// `std::error::Error`s, strings, `anyhow::Error` and `eyre::Report` included
+    fn internal(self) -> Result<T, JsonError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    fn json_err(self, status: StatusCode, code: &'static str) -> Result<T, JsonError> {
+        self.map_err(|_| JsonError::new(status, code))
+    }
+
+    fn or_not_found(self, code: &'static str) -> Result<T, JsonError> {
+        self.json_err(StatusCode::NOT_FOUND, code)
+    }
+
+    fn internal(self) -> Result<T, JsonError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.map_err(|err| {
+            crate::internal::internal_error(
+                std::any::type_name::<E>(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal-error",
+                &*err.into(),
+                None,
+            )
+        })
+    }
+}
+
+#[allow(clippy::result_large_err)]
+pub trait OptionExt<T> {
+    /// Turn `None` into a `JsonError`
+    fn json_err(self, status: StatusCode, code: &'static str) -> Result<T, JsonError>;
+
+    /// Turn `None` into a `404` error
+    fn or_not_found(self, code: &'static str) -> Result<T, JsonError>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    fn json_err(self, status: StatusCode, code: &'static str) -> Result<T, JsonError> {
+        self.ok_or_else(|| JsonError::new(status, code))
+    }
+
+    fn or_not_found(self, code: &'static str) -> Result<T, JsonError> {
+        self.json_err(StatusCode::NOT_FOUND, code)
+    }
+}
@@ -0,0 +1,25 @@
+use axum::http::StatusCode;
+
+use crate::{
+    render::{Render, Sink},
+    JsonError,
+};
+
+/// Converts the error into an internal error, logging/reporting its whole chain
+impl From<eyre::Report> for JsonError {
+    fn from(err: eyre::Report) -> Self {
+        crate::internal::internal_error(
+            "eyre::Report",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal-error",
+            &*err,
+            None,
+        )
+    }
+}
+
+impl Render for eyre::Report {
+    fn render<S: Sink>(self) -> S {
+        S::from_error(JsonError::from(self))
+    }
+}
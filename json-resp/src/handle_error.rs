@@ -0,0 +1,117 @@
+//! Error handlers for `HandleErrorLayer`.
+//!
+//! Fallible tower middlewares(`timeout`, `load_shed`, ...) need an error handler to be used with
+//! axum, [`handle_error`] maps their errors to json-resp errors:
+//!
+//! - `Elapsed` responds with `408 request-timeout`([`handle_error_as_gateway`] responds with
+//!   `504 gateway-timeout` instead)
+//! - `Overloaded` responds with `503 overloaded` and a `Retry-After` header
+//! - Any other error is an internal error
+//!
+//! With `openapi` feature, [`TowerError`] can be used in the `responses` of a path to document all
+//! of them.
+
+use axum::BoxError;
+use tower::{load_shed::error::Overloaded, timeout::error::Elapsed};
+
+use crate::JsonError;
+
+#[derive(Debug, JsonError)]
+pub enum TowerError {
+    #[json_error(
+        request,
+        status = 408,
+        code = "request-timeout",
+        description = "The request took too long to be handled"
+    )]
+    Timeout,
+
+    #[json_error(
+        request,
+        status = 504,
+        code = "gateway-timeout",
+        description = "The request took too long to be handled"
+    )]
+    GatewayTimeout,
+
+    #[json_error(
+        request,
+        status = 503,
+        code = "overloaded",
+        description = "The service is overloaded, try again later",
+        header("Retry-After" = "1")
+    )]
+    Overloaded,
+
+    #[json_error(internal)]
+    Internal(BoxError),
+}
+
+impl From<BoxError> for TowerError {
+    fn from(err: BoxError) -> Self {
+        if err.is::<Elapsed>() {
+            Self::Timeout
+        } else if err.is::<Overloaded>() {
+            Self::Overloaded
+        } else {
+            Self::Internal(err)
+        }
+    }
+}
+
+/// Handle the errors of tower middlewares, to be used with `HandleErrorLayer`
+///
+/// ```ignore
+/// let app = Router::new().route("/", get(handler)).layer(
+///     ServiceBuilder::new()
+///         .layer(HandleErrorLayer::new(json_resp::handle_error::handle_error))
+///         .timeout(Duration::from_secs(10)),
+/// );
+/// ```
+pub async fn handle_error(err: BoxError) -> TowerError {
+    TowerError::from(err)
+}
+
+/// Same as [`handle_error`], but responds to timeouts with `504 gateway-timeout`
+pub async fn handle_error_as_gateway(err: BoxError) -> TowerError {
+    match TowerError::from(err) {
+        TowerError::Timeout => TowerError::GatewayTimeout,
+        err => err,
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::IntoResponses for TowerError {
+    fn responses(
+    ) -> std::collections::BTreeMap<String, utoipa::openapi::RefOr<utoipa::openapi::Response>> {
+        use utoipa::{
+            openapi::{RefOr, Response},
+            IntoResponses, ToResponse, ToSchema,
+        };
+
+        // The schemas are inlined, so they don't have to be registered as components
+        fn inlined<T>() -> (String, RefOr<Response>)
+        where
+            T: ToSchema<'static> + ToResponse<'static> + IntoResponses,
+        {
+            let status = T::responses().into_keys().next().unwrap();
+            let mut response = match T::response().1 {
+                RefOr::T(response) => response,
+                RefOr::Ref(_) => unreachable!(),
+            };
+            for content in response.content.values_mut() {
+                content.schema = T::schema().1;
+            }
+            (status, response.into())
+        }
+
+        [
+            inlined::<TowerErrorOai::Timeout>(),
+            inlined::<TowerErrorOai::GatewayTimeout>(),
+            inlined::<TowerErrorOai::Overloaded>(),
+            inlined::<TowerErrorOai::InternalError>(),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
@@ -0,0 +1,76 @@
+//! Internal errors built outside of the derive, e.g. for panics or with `ResultExt::internal`.
+
+use std::{backtrace::Backtrace, error::Error};
+
+use axum::http::StatusCode;
+
+use crate::{reporter::ErrorReport, JsonError};
+
+/// Build an internal error out of `error`, reporting it the same way the derive does. `backtrace`
+/// is the one captured by the error, if any
+pub(crate) fn internal_error(
+    name: &'static str,
+    status: StatusCode,
+    code: &'static str,
+    error: &dyn Error,
+    backtrace: Option<&Backtrace>,
+) -> JsonError {
+    #[cfg(feature = "error-id")]
+    let error_id = crate::error_id::current();
+
+    #[cfg(all(feature = "log", feature = "error-id"))]
+    log::error!(
+        "{} [{}] {}",
+        name,
+        error_id,
+        crate::error_chain::Chain(error)
+    );
+    #[cfg(all(feature = "log", not(feature = "error-id")))]
+    log::error!("{} {}", name, crate::error_chain::Chain(error));
+
+    #[cfg(all(feature = "tracing", feature = "error-id"))]
+    tracing::error!(
+        error.code = code,
+        http.status = status.as_u16(),
+        error.variant = name,
+        error.source = %crate::error_chain::Chain(error),
+        error.id = error_id.as_str(),
+        "internal error"
+    );
+    #[cfg(all(feature = "tracing", not(feature = "error-id")))]
+    tracing::error!(
+        error.code = code,
+        http.status = status.as_u16(),
+        error.variant = name,
+        error.source = %crate::error_chain::Chain(error),
+        "internal error"
+    );
+
+    crate::reporter::report(&ErrorReport {
+        type_name: name,
+        variant: None,
+        code,
+        status,
+        internal: true,
+        error: Some(error),
+    });
+
+    #[allow(unused_mut)]
+    let mut json_error = JsonError::new(status, code).error_type(name);
+
+    #[cfg(feature = "error-id")]
+    {
+        json_error = json_error.error_id(error_id);
+    }
+
+    #[cfg(not(feature = "debug-errors"))]
+    let _ = backtrace;
+    #[cfg(feature = "debug-errors")]
+    if let Some(debug) =
+        crate::debug::debug_info(name, || crate::error_chain::messages(error), backtrace)
+    {
+        json_error = json_error.debug(debug);
+    }
+
+    json_error
+}
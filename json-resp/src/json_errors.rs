@@ -0,0 +1,157 @@
+use std::{error::Error, fmt};
+
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{DynJsonError, Envelope, JsonError};
+
+/// How the status of a [`JsonErrors`] response is picked
+#[derive(Debug, Clone, Copy, Default)]
+pub enum AggregateStatus {
+    /// The status of the first error
+    #[default]
+    First,
+    /// The highest status of the errors
+    Highest,
+    Fixed(StatusCode),
+}
+
+/// Several errors responded at once, e.g. for bulk operations or validations
+///
+/// ```json5
+/// {
+///     "status": 400,
+///     "code": "multiple-errors",
+///     "errors": [{"status": 400, "code": "invalid-email", "content": null}, ...]
+/// }
+/// ```
+///
+/// The headers of the errors are merged into the response. Responding with no errors is
+/// considered an internal error, it's logged/reported and responded as one.
+#[derive(Debug, Default)]
+pub struct JsonErrors {
+    pub errors: Vec<JsonError<Value>>,
+    pub status: AggregateStatus,
+}
+
+impl JsonErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(mut self, status: AggregateStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Add an error, anything convertible into a [`DynJsonError`] can be added
+    pub fn push<E: Into<DynJsonError>>(&mut self, error: E) {
+        self.errors.push(error.into().into_inner());
+    }
+
+    pub fn error<E: Into<DynJsonError>>(mut self, error: E) -> Self {
+        self.push(error);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    fn response_status(&self) -> StatusCode {
+        let mut statuses = self.errors.iter().map(|error| error.status);
+        let status = match self.status {
+            AggregateStatus::First => statuses.next(),
+            AggregateStatus::Highest => statuses.max(),
+            AggregateStatus::Fixed(status) => Some(status),
+        };
+        status.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl<E: Into<DynJsonError>> FromIterator<E> for JsonErrors {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        Self {
+            errors: iter
+                .into_iter()
+                .map(|error| error.into().into_inner())
+                .collect(),
+            status: AggregateStatus::default(),
+        }
+    }
+}
+
+impl<E: Into<DynJsonError>> Extend<E> for JsonErrors {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for error in iter {
+            self.push(error);
+        }
+    }
+}
+
+/// Reported when a `JsonErrors` is responded without any errors
+#[derive(Debug)]
+struct NoErrors;
+
+impl fmt::Display for NoErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JsonErrors responded without any errors")
+    }
+}
+
+impl Error for NoErrors {}
+
+#[derive(Serialize)]
+struct Body<'a> {
+    status: u16,
+    code: &'static str,
+    errors: &'a [JsonError<Value>],
+}
+
+impl IntoResponse for JsonErrors {
+    fn into_response(mut self) -> Response {
+        if self.errors.is_empty() {
+            return crate::internal::internal_error(
+                "JsonErrors",
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal-error",
+                &NoErrors,
+                None,
+            )
+            .into_response();
+        }
+
+        let status = self.response_status();
+
+        let mut headers = HeaderMap::new();
+        for error in &mut self.errors {
+            headers.extend(std::mem::take(&mut error.headers));
+        }
+
+        let mut response = (
+            status,
+            headers,
+            Json(Body {
+                status: status.as_u16(),
+                code: "multiple-errors",
+                errors: &self.errors,
+            }),
+        )
+            .into_response();
+        response.extensions_mut().insert(Envelope);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record("multiple-errors", status, Some("JsonErrors"), &response);
+
+        response
+    }
+}
@@ -1,9 +1,66 @@
 //! This crate contains a set of structs and macros to ease the implementation of REST apis
 
+// The derive refers to this crate as `json_resp`
+extern crate self as json_resp;
+
+mod batch;
+mod dyn_error;
+mod error_chain;
+mod ext;
+mod internal;
+mod json_errors;
+mod render;
 mod response;
+mod routing;
+mod status;
+
+pub use batch::{BatchEntry, JsonBatchMeta, JsonBatchResponse};
+pub use dyn_error::DynJsonError;
+pub use ext::{OptionExt, ResultExt};
+pub use json_errors::{AggregateStatus, JsonErrors};
+pub use json_resp_derive::{JsonError, JsonSuccess};
+pub use response::{
+    Envelope, JsonError, JsonErrorDebug, JsonErrorMeta, JsonList, JsonListMeta, JsonResponse,
+    Nothing,
+};
+
+pub use routing::{fallback, method_not_allowed, AllowedMethods, RouterExt};
+pub use status::{Accepted, Created, NoContent};
+
+pub mod reporter;
+
+#[cfg(feature = "debug-errors")]
+pub mod debug;
+
+#[cfg(feature = "error-id")]
+pub mod error_id;
+
+#[cfg(feature = "error-id")]
+pub use error_id::ErrorIdLayer;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "catch-panic")]
+pub mod panic;
+
+#[cfg(feature = "catch-panic")]
+pub use panic::CatchPanicLayer;
 
-pub use json_resp_derive::JsonError;
-pub use response::{JsonError, JsonListMeta, JsonResponse, Nothing};
+#[cfg(feature = "handle-error")]
+pub mod handle_error;
+
+#[cfg(feature = "normalize")]
+pub mod normalize;
+
+#[cfg(feature = "normalize")]
+pub use normalize::NormalizeErrorsLayer;
+
+#[cfg(feature = "anyhow")]
+mod anyhow_impls;
+
+#[cfg(feature = "eyre")]
+mod eyre_impls;
 
 #[cfg(feature = "openapi")]
 mod utoipa_impls;
@@ -15,19 +72,42 @@ pub type JsonResult<T, E = Nothing> = Result<JsonResponse<T>, JsonError<E>>;
 
 #[doc(hidden)]
 pub mod __private {
-    pub use axum::response::{IntoResponse, Response};
+    pub use axum::{
+        http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+        response::{IntoResponse, Response},
+    };
+
+    pub use crate::error_chain::{
+        find_source, Downcasted, ErrorRef, ViaBacktrace, ViaDisplay, ViaDynError, ViaError,
+        ViaNoBacktrace, ViaNothing,
+    };
+    pub use crate::render::{Render, Sink};
+    pub use crate::reporter::report;
+    pub use serde_json;
 
     #[cfg(feature = "log")]
-    pub use log::error as log_error;
+    pub use log;
+
+    #[cfg(feature = "tracing")]
+    pub use tracing;
+
+    #[cfg(feature = "error-id")]
+    pub use crate::error_id::current as error_id;
+
+    #[cfg(feature = "debug-errors")]
+    pub use crate::debug::debug_info;
 
     #[cfg(feature = "openapi")]
     pub mod utoipa {
         pub use utoipa::{
             openapi::{
-                ContentBuilder, KnownFormat, ObjectBuilder, Ref, RefOr, Response, ResponseBuilder,
-                ResponsesBuilder, Schema, SchemaFormat, SchemaType,
+                ContentBuilder, HeaderBuilder, KnownFormat, ObjectBuilder, OneOfBuilder, Ref,
+                RefOr, Response, ResponseBuilder, ResponsesBuilder, Schema, SchemaFormat,
+                SchemaType,
             },
             IntoResponses, ToResponse, ToSchema,
         };
+
+        pub use crate::utoipa_impls::{json_response_schema, merge_responses, ErrorDocs};
     }
 }
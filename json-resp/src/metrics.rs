@@ -0,0 +1,42 @@
+//! Error metrics through the `metrics` facade.
+//!
+//! Every rendered [`JsonError`](crate::JsonError) increments the `json_resp_errors_total` counter
+//! labeled with its `code`, `status` and `error`(the name of the derived type, `JsonError` for the
+//! errors built manually). The size of the error responses can be recorded in the
+//! `json_resp_error_response_size_bytes` histogram too, after enabling it with
+//! [`record_response_size`].
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use axum::{body::HttpBody, http::StatusCode, response::Response};
+
+pub const ERRORS_TOTAL: &str = "json_resp_errors_total";
+pub const ERROR_RESPONSE_SIZE: &str = "json_resp_error_response_size_bytes";
+
+static RESPONSE_SIZE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable the response size histogram, disabled by default
+pub fn record_response_size(enabled: bool) {
+    RESPONSE_SIZE.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn record(
+    code: &'static str,
+    status: StatusCode,
+    error_type: Option<&'static str>,
+    response: &Response,
+) {
+    let labels = [
+        ("code", code.to_string()),
+        ("status", status.as_u16().to_string()),
+        ("error", error_type.unwrap_or("JsonError").to_string()),
+    ];
+
+    ::metrics::counter!(ERRORS_TOTAL, &labels).increment(1);
+
+    if RESPONSE_SIZE.load(Ordering::Relaxed) {
+        if let Some(size) = response.body().size_hint().exact() {
+            ::metrics::histogram!(ERROR_RESPONSE_SIZE, &labels).record(size as f64);
+        }
+    }
+}
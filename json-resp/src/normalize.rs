@@ -0,0 +1,180 @@
+//! Wrapping the error responses of other services in the json-resp envelope.
+//!
+//! Fallbacks, extractor rejections and other middlewares respond with bodies of their own,
+//! [`NormalizeErrorsLayer`] replaces any `4xx`/`5xx` response that isn't built by json-resp with a
+//! [`JsonError`] of the same status. Envelope responses are detected with the [`Envelope`]
+//! extension, the bodies are never inspected. `405`s list the methods of the `Allow` header like
+//! [`method_not_allowed`](crate::method_not_allowed).
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{self, BoxBody, Bytes, HttpBody},
+    http::{header, Request, Response, StatusCode},
+    response::IntoResponse,
+    BoxError,
+};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{AllowedMethods, Envelope, JsonError};
+
+/// Inserted in the responses built by [`NormalizeErrorsLayer`], so
+/// [`method_not_allowed`](crate::method_not_allowed) can still replace its `405`s when the layer
+/// is installed inside the router(before axum adds the `Allow` header)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Normalized;
+
+/// The code used for a status unless it's overridden
+pub fn default_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "bad-request",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not-found",
+        StatusCode::METHOD_NOT_ALLOWED => "method-not-allowed",
+        StatusCode::NOT_ACCEPTABLE => "not-acceptable",
+        StatusCode::REQUEST_TIMEOUT => "request-timeout",
+        StatusCode::CONFLICT => "conflict",
+        StatusCode::GONE => "gone",
+        StatusCode::LENGTH_REQUIRED => "length-required",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload-too-large",
+        StatusCode::URI_TOO_LONG => "uri-too-long",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported-media-type",
+        StatusCode::UNPROCESSABLE_ENTITY => "unprocessable-entity",
+        StatusCode::TOO_MANY_REQUESTS => "too-many-requests",
+        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE => "request-header-fields-too-large",
+        StatusCode::INTERNAL_SERVER_ERROR => "internal-error",
+        StatusCode::NOT_IMPLEMENTED => "not-implemented",
+        StatusCode::BAD_GATEWAY => "bad-gateway",
+        StatusCode::SERVICE_UNAVAILABLE => "service-unavailable",
+        StatusCode::GATEWAY_TIMEOUT => "gateway-timeout",
+        status if status.is_client_error() => "client-error",
+        _ => "server-error",
+    }
+}
+
+/// A layer wrapping the error responses of the inner service in the json-resp envelope
+#[derive(Debug, Clone, Default)]
+pub struct NormalizeErrorsLayer {
+    codes: Arc<HashMap<StatusCode, &'static str>>,
+}
+
+impl NormalizeErrorsLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use another code for the responses with the given status
+    pub fn code(mut self, status: StatusCode, code: &'static str) -> Self {
+        Arc::make_mut(&mut self.codes).insert(status, code);
+        self
+    }
+}
+
+impl<S> Layer<S> for NormalizeErrorsLayer {
+    type Service = NormalizeErrors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        NormalizeErrors {
+            inner,
+            codes: self.codes.clone(),
+        }
+    }
+}
+
+/// The service created by [`NormalizeErrorsLayer`]
+#[derive(Debug, Clone)]
+pub struct NormalizeErrors<S> {
+    inner: S,
+    codes: Arc<HashMap<StatusCode, &'static str>>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for NormalizeErrors<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        ResponseFuture {
+            future: self.inner.call(req),
+            codes: self.codes.clone(),
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of [`NormalizeErrors`]
+    pub struct ResponseFuture<F> {
+        #[pin]
+        future: F,
+        codes: Arc<HashMap<StatusCode, &'static str>>,
+    }
+}
+
+impl<F, ResBody, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<ResBody>, E>>,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Output = Result<Response<BoxBody>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = match this.future.poll(cx) {
+            Poll::Ready(Ok(response)) => response,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let status = response.status();
+        if !(status.is_client_error() || status.is_server_error())
+            || response.extensions().get::<Envelope>().is_some()
+        {
+            return Poll::Ready(Ok(response.map(body::boxed)));
+        }
+
+        let code = match this.codes.get(&status) {
+            Some(code) => code,
+            None => default_code(status),
+        };
+
+        // Keep the headers describing the error(e.g. `Allow` or `Retry-After`) but not the ones
+        // describing the replaced body
+        let mut headers = response.into_parts().0.headers;
+        headers.remove(header::CONTENT_TYPE);
+        headers.remove(header::CONTENT_LENGTH);
+        headers.remove(header::CONTENT_ENCODING);
+
+        let mut response = match status {
+            StatusCode::METHOD_NOT_ALLOWED => {
+                let allowed = AllowedMethods::from_header(headers.get(header::ALLOW));
+                JsonError::with_content(status, code, allowed)
+                    .headers(headers)
+                    .into_response()
+            }
+            _ => JsonError::new(status, code)
+                .headers(headers)
+                .into_response(),
+        };
+        response.extensions_mut().insert(Normalized);
+        Poll::Ready(Ok(response))
+    }
+}
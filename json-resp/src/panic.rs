@@ -0,0 +1,94 @@
+//! Turning panics into internal errors.
+//!
+//! [`CatchPanicLayer`] catches the panics of the inner service and responds with the same
+//! envelope as the internal errors of the derive(`500` with `internal-error` code by default). The
+//! panic message is logged/traced with the enabled integrations and passed to the registered
+//! [`ErrorReporter`](crate::reporter::ErrorReporter).
+
+use std::{any::Any, error::Error, fmt};
+
+use axum::{
+    body::BoxBody,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use tower_http::catch_panic::ResponseForPanic;
+
+/// A tower layer converting panics into internal errors
+pub type CatchPanicLayer = tower_http::catch_panic::CatchPanicLayer<PanicHandler>;
+
+/// Create a [`CatchPanicLayer`] responding with the default status and code
+pub fn layer() -> CatchPanicLayer {
+    CatchPanicLayer::custom(PanicHandler::new())
+}
+
+/// Builds the response of a caught panic
+#[derive(Debug, Clone)]
+pub struct PanicHandler {
+    status: StatusCode,
+    code: &'static str,
+}
+
+impl PanicHandler {
+    pub fn new() -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "internal-error",
+        }
+    }
+
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn code(mut self, code: &'static str) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Create a [`CatchPanicLayer`] using this handler
+    pub fn layer(self) -> CatchPanicLayer {
+        CatchPanicLayer::custom(self)
+    }
+}
+
+impl Default for PanicHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResponseForPanic for PanicHandler {
+    type ResponseBody = BoxBody;
+
+    fn response_for_panic(&mut self, err: Box<dyn Any + Send + 'static>) -> Response<BoxBody> {
+        let panic = Panic::from_payload(&*err);
+        crate::internal::internal_error("panic", self.status, self.code, &panic, None)
+            .into_response()
+    }
+}
+
+/// The message of a panic
+#[derive(Debug)]
+struct Panic(String);
+
+impl Panic {
+    fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            Self(message.to_string())
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            Self(message.clone())
+        } else {
+            Self(String::from("unknown panic message"))
+        }
+    }
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for Panic {}
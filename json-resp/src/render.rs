@@ -0,0 +1,36 @@
+//! Rendering errors into different outputs.
+//!
+//! The derive builds a `JsonError` for each variant and hands it to a [`Sink`], which is either a
+//! response or a [`DynJsonError`], so the same code is used for both.
+
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::{DynJsonError, JsonError};
+
+pub trait Sink {
+    fn from_error<T: Serialize + 'static>(error: JsonError<T>) -> Self;
+}
+
+impl Sink for Response {
+    fn from_error<T: Serialize + 'static>(error: JsonError<T>) -> Self {
+        error.into_response()
+    }
+}
+
+impl Sink for DynJsonError {
+    fn from_error<T: Serialize + 'static>(error: JsonError<T>) -> Self {
+        DynJsonError::new(error)
+    }
+}
+
+/// Implemented for `JsonError`s and the derived types
+pub trait Render {
+    fn render<S: Sink>(self) -> S;
+}
+
+impl<T: Serialize + 'static> Render for JsonError<T> {
+    fn render<S: Sink>(self) -> S {
+        S::from_error(self)
+    }
+}
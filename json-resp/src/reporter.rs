@@ -0,0 +1,116 @@
+//! Forwarding errors to external services.
+//!
+//! A single [`ErrorReporter`] can be registered at startup with [`set_reporter`], after that every
+//! response built by a derived `JsonError` is reported to it, whether it's an internal or a request
+//! error. Transparent variants are reported by the inner error.
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use axum::http::StatusCode;
+
+static REPORTER: OnceLock<Box<dyn ErrorReporter>> = OnceLock::new();
+
+/// What is known about an error when its response is being built
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorReport<'a> {
+    /// Name of the derived type
+    pub type_name: &'static str,
+    /// Name of the variant, `None` for structs
+    pub variant: Option<&'static str>,
+    pub code: &'static str,
+    pub status: StatusCode,
+    pub internal: bool,
+    /// The content of the error if it implements `std::error::Error`
+    pub error: Option<&'a dyn Error>,
+}
+
+pub trait ErrorReporter: Send + Sync + 'static {
+    fn report(&self, report: &ErrorReport<'_>);
+}
+
+/// Returned by [`set_reporter`] when a reporter is already registered
+#[derive(Debug)]
+pub struct SetReporterError(());
+
+impl fmt::Display for SetReporterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an error reporter is already registered")
+    }
+}
+
+impl Error for SetReporterError {}
+
+/// Register the global reporter, can only be done once
+pub fn set_reporter<R: ErrorReporter>(reporter: R) -> Result<(), SetReporterError> {
+    REPORTER
+        .set(Box::new(reporter))
+        .map_err(|_| SetReporterError(()))
+}
+
+#[doc(hidden)]
+pub fn report(report: &ErrorReport<'_>) {
+    if let Some(reporter) = REPORTER.get() {
+        reporter.report(report);
+    }
+}
+
+/// An owned copy of an [`ErrorReport`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportedError {
+    pub type_name: &'static str,
+    pub variant: Option<&'static str>,
+    pub code: &'static str,
+    pub status: StatusCode,
+    pub internal: bool,
+    /// The error and its sources separated by `": "`
+    pub error: Option<String>,
+}
+
+impl<'a> From<&ErrorReport<'a>> for ReportedError {
+    fn from(report: &ErrorReport<'a>) -> Self {
+        Self {
+            type_name: report.type_name,
+            variant: report.variant,
+            code: report.code,
+            status: report.status,
+            internal: report.internal,
+            error: report
+                .error
+                .map(|err| crate::error_chain::Chain(err).to_string()),
+        }
+    }
+}
+
+/// A reporter keeping the reports in memory, mostly useful in tests
+///
+/// Clones share the same storage, so a clone can be registered while the original is kept around
+/// to inspect the reports.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReporter {
+    reports: Arc<Mutex<Vec<ReportedError>>>,
+}
+
+impl MemoryReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All the errors reported so far
+    pub fn reports(&self) -> Vec<ReportedError> {
+        self.reports.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.reports.lock().unwrap().clear();
+    }
+}
+
+impl ErrorReporter for MemoryReporter {
+    fn report(&self, report: &ErrorReport<'_>) {
+        self.reports.lock().unwrap().push(report.into());
+    }
+}
@@ -1,13 +1,13 @@
 use std::fmt;
 
 use axum::{
-    http::StatusCode,
+    http::{header::IntoHeaderName, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
 
-fn as_u16<S>(status: &StatusCode, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn as_u16<S>(status: &StatusCode, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
@@ -17,6 +17,11 @@ where
 #[derive(Debug, Default, Serialize)]
 pub struct Nothing;
 
+/// Inserted in the extensions of the responses built by json-resp, so middlewares can tell them
+/// apart from other responses
+#[derive(Debug, Clone, Copy)]
+pub struct Envelope;
+
 #[derive(Debug, Default, Serialize)]
 pub struct JsonListMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -44,6 +49,29 @@ impl JsonListMeta {
     }
 }
 
+/// A list of items used as the content of a `JsonResponse`, usually along with a [`JsonListMeta`]
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct JsonList<T>(pub Vec<T>);
+
+impl<T> Default for JsonList<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> From<Vec<T>> for JsonList<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T> FromIterator<T> for JsonList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct JsonResponse<T = Nothing, M = Nothing> {
     #[serde(serialize_with = "as_u16")]
@@ -117,12 +145,36 @@ where
     M: Serialize,
 {
     fn into_response(self) -> Response {
-        (self.status, Json(&self)).into_response()
+        let mut response = (self.status, Json(&self)).into_response();
+        response.extensions_mut().insert(Envelope);
+        response
     }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Debug, Default, Serialize)]
+pub struct JsonErrorMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_id: Option<String>,
+}
+
+impl JsonErrorMeta {
+    pub fn is_empty(&self) -> bool {
+        self.error_id.is_none()
+    }
+}
+
+/// Details of an internal error, only included in debug mode
+#[derive(Debug, Default, Serialize)]
+pub struct JsonErrorDebug {
+    pub error: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+}
+
 #[derive(Default, Debug, Serialize)]
 pub struct JsonError<T = Nothing> {
     #[serde(serialize_with = "as_u16")]
@@ -131,6 +183,15 @@ pub struct JsonError<T = Nothing> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hint: Option<String>,
     pub content: T,
+    #[serde(skip_serializing_if = "JsonErrorMeta::is_empty")]
+    pub meta: JsonErrorMeta,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<JsonErrorDebug>,
+    #[serde(skip)]
+    pub headers: HeaderMap,
+    /// Name of the type the error is derived from, if any
+    #[serde(skip)]
+    pub error_type: Option<&'static str>,
 }
 
 impl JsonError {
@@ -150,6 +211,10 @@ impl<T> JsonError<T> {
             code,
             hint: None,
             content,
+            meta: JsonErrorMeta::default(),
+            debug: None,
+            headers: HeaderMap::new(),
+            error_type: None,
         }
     }
 
@@ -158,12 +223,46 @@ impl<T> JsonError<T> {
         self
     }
 
+    /// Set the id used to correlate the response with the logs
+    pub fn error_id(mut self, error_id: String) -> Self {
+        self.meta.error_id = Some(error_id);
+        self
+    }
+
+    /// Attach the details of the error, meant to be used in development only
+    pub fn debug(mut self, debug: JsonErrorDebug) -> Self {
+        self.debug = Some(debug);
+        self
+    }
+
+    /// Add a header to the response, replacing the previous value if any
+    pub fn header<K: IntoHeaderName>(mut self, key: K, value: HeaderValue) -> Self {
+        self.headers.insert(key, value);
+        self
+    }
+
+    /// Add all the headers to the response, replacing the previous values if any
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Set the name of the type the error is derived from
+    pub fn error_type(mut self, error_type: &'static str) -> Self {
+        self.error_type = Some(error_type);
+        self
+    }
+
     pub fn content<B>(self, content: B) -> JsonError<B> {
         JsonError {
             status: self.status,
             code: self.code,
             hint: self.hint,
             content,
+            meta: self.meta,
+            debug: self.debug,
+            headers: self.headers,
+            error_type: self.error_type,
         }
     }
 }
@@ -179,7 +278,14 @@ impl<T> IntoResponse for JsonError<T>
 where
     T: Serialize + 'static,
 {
-    fn into_response(self) -> axum::response::Response {
-        (self.status, Json(&self)).into_response()
+    fn into_response(mut self) -> axum::response::Response {
+        let headers = std::mem::take(&mut self.headers);
+        let mut response = (self.status, headers, Json(&self)).into_response();
+        response.extensions_mut().insert(Envelope);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(self.code, self.status, self.error_type, &response);
+
+        response
     }
 }
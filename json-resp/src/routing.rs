@@ -0,0 +1,93 @@
+//! Responding to unknown routes and methods with json-resp errors.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use serde::Serialize;
+
+use crate::{Envelope, JsonError};
+
+/// Content of the `405 method-not-allowed` errors
+#[derive(Debug, Default, Serialize)]
+pub struct AllowedMethods {
+    pub allowed: Vec<String>,
+}
+
+impl AllowedMethods {
+    /// The methods listed in an `Allow` header
+    pub fn from_header(allow: Option<&HeaderValue>) -> Self {
+        let allowed = allow
+            .and_then(|allow| allow.to_str().ok())
+            .map(|allow| {
+                allow
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|method| !method.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { allowed }
+    }
+}
+
+/// A fallback handler responding with `404 route-not-found`
+pub async fn fallback() -> JsonError {
+    JsonError::new(StatusCode::NOT_FOUND, "route-not-found")
+}
+
+/// A middleware replacing the `405` responses of axum with `405 method-not-allowed` errors,
+/// listing the allowed methods in the content and the `Allow` header
+///
+/// It should wrap the whole router to see the `Allow` header, see [`RouterExt`].
+pub async fn method_not_allowed(req: Request<Body>, next: Next<Body>) -> Response {
+    let response = next.run(req).await;
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED || !is_replaceable(&response) {
+        return response;
+    }
+
+    let allow = response.headers().get(header::ALLOW).cloned();
+    let error = JsonError::with_content(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "method-not-allowed",
+        AllowedMethods::from_header(allow.as_ref()),
+    );
+    match allow {
+        Some(allow) => error.header(header::ALLOW, allow).into_response(),
+        None => error.into_response(),
+    }
+}
+
+/// Responses of json-resp are kept, except for the ones of `NormalizeErrorsLayer` which runs inside
+/// the router, before axum adds the `Allow` header
+fn is_replaceable(response: &Response) -> bool {
+    #[cfg(feature = "normalize")]
+    if response
+        .extensions()
+        .get::<crate::normalize::Normalized>()
+        .is_some()
+    {
+        return true;
+    }
+    response.extensions().get::<Envelope>().is_none()
+}
+
+/// Installs the json-resp [`fallback`] and [`method_not_allowed`] handlers on a router
+///
+/// The `Allow` header is added by axum after the layers of a router, so the router gets wrapped
+/// in another one to see it, which is why the state has to be provided first.
+pub trait RouterExt {
+    fn json_fallbacks(self) -> Self;
+}
+
+impl RouterExt for Router<(), Body> {
+    fn json_fallbacks(self) -> Self {
+        Router::new()
+            .fallback_service(self.fallback(fallback))
+            .layer(middleware::from_fn(method_not_allowed))
+    }
+}
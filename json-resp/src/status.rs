@@ -0,0 +1,108 @@
+//! Success responses with a status known at compile time.
+//!
+//! Unlike [`JsonResponse`], whose status is set at runtime, these are documented with their own
+//! status in openapi, so the return type of a handler can be used directly in its responses.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::{JsonResponse, Nothing};
+
+macro_rules! typed_status {
+    ($(#[$doc:meta])* $name:ident, $status:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Default)]
+        pub struct $name<T = Nothing, M = Nothing> {
+            pub content: T,
+            pub meta: M,
+        }
+
+        impl<T> $name<T> {
+            pub fn with_content(content: T) -> Self {
+                Self {
+                    content,
+                    meta: Nothing,
+                }
+            }
+        }
+
+        impl<T, M> $name<T, M> {
+            pub fn meta<M2>(self, meta: M2) -> $name<T, M2> {
+                $name {
+                    content: self.content,
+                    meta,
+                }
+            }
+        }
+
+        impl<T, M> From<$name<T, M>> for JsonResponse<T, M> {
+            fn from(response: $name<T, M>) -> Self {
+                JsonResponse {
+                    status: StatusCode::$status,
+                    content: response.content,
+                    meta: response.meta,
+                }
+            }
+        }
+
+        impl<T, M> IntoResponse for $name<T, M>
+        where
+            T: Serialize,
+            M: Serialize,
+        {
+            fn into_response(self) -> Response {
+                JsonResponse::from(self).into_response()
+            }
+        }
+
+        #[cfg(feature = "openapi")]
+        impl<'__r, T, M> utoipa::IntoResponses for $name<T, M>
+        where
+            T: utoipa::ToSchema<'__r>,
+            M: utoipa::ToSchema<'__r>,
+        {
+            fn responses() -> std::collections::BTreeMap<
+                String,
+                utoipa::openapi::RefOr<utoipa::openapi::Response>,
+            > {
+                crate::utoipa_impls::typed_responses::<T, M>(StatusCode::$status)
+            }
+        }
+    };
+}
+
+typed_status!(
+    /// A `201` response, e.g. `Created::with_content(user)`
+    Created,
+    CREATED
+);
+
+typed_status!(
+    /// A `202` response for requests which will be processed later
+    Accepted,
+    ACCEPTED
+);
+
+/// A `204` response, it has no body
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoContent;
+
+impl IntoResponse for NoContent {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::IntoResponses for NoContent {
+    fn responses(
+    ) -> std::collections::BTreeMap<String, utoipa::openapi::RefOr<utoipa::openapi::Response>> {
+        utoipa::openapi::ResponsesBuilder::new()
+            .response("204", utoipa::openapi::Response::new("No Content"))
+            .build()
+            .into()
+    }
+}
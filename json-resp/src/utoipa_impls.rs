@@ -1,103 +1,449 @@
-use std::{collections::BTreeMap, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, HashSet},
+    marker::PhantomData,
+    sync::{Mutex, OnceLock},
+};
+
+use axum::http::StatusCode;
 
 use utoipa::{
     openapi::{
-        ContentBuilder, KnownFormat, ObjectBuilder, OneOfBuilder, Ref, RefOr, Response,
-        ResponseBuilder, ResponsesBuilder, Schema, SchemaFormat, SchemaType,
+        AllOfBuilder, ArrayBuilder, ContentBuilder, KnownFormat, ObjectBuilder, OneOfBuilder, Ref,
+        RefOr, Response, ResponseBuilder, ResponsesBuilder, Schema, SchemaFormat, SchemaType,
     },
     IntoResponses, ToSchema,
 };
 
-use crate::{JsonResponse, Nothing};
+use crate::{
+    JsonBatchResponse, JsonError, JsonErrors, JsonList, JsonListMeta, JsonResponse, Nothing,
+};
 
-/// A struct that can be used to combine 2 errors with the same status code
-pub struct CombineErrors<E1, E2>(PhantomData<dyn Fn() -> (E1, E2)>);
+/// Combines the responses of up to 8 errors, the ones sharing a status are documented as either
+/// of them
+#[allow(clippy::type_complexity)]
+pub struct CombineErrors<
+    E1,
+    E2,
+    E3 = Nothing,
+    E4 = Nothing,
+    E5 = Nothing,
+    E6 = Nothing,
+    E7 = Nothing,
+    E8 = Nothing,
+>(PhantomData<dyn Fn() -> (E1, E2, E3, E4, E5, E6, E7, E8)>);
 
-impl<'__r, E1, E2> IntoResponses for CombineErrors<E1, E2>
+impl<E1, E2, E3, E4, E5, E6, E7, E8> IntoResponses for CombineErrors<E1, E2, E3, E4, E5, E6, E7, E8>
 where
-    E1: ToSchema<'__r> + IntoResponses,
-    E2: ToSchema<'__r> + IntoResponses,
+    E1: IntoResponses,
+    E2: IntoResponses,
+    E3: IntoResponses,
+    E4: IntoResponses,
+    E5: IntoResponses,
+    E6: IntoResponses,
+    E7: IntoResponses,
+    E8: IntoResponses,
 {
     fn responses() -> BTreeMap<String, RefOr<Response>> {
-        let status1 = match E1::responses().into_iter().next() {
-            Some((status1, _)) => status1,
-            _ => {
-                panic!("Not supported")
+        merge_responses([
+            E1::responses(),
+            E2::responses(),
+            E3::responses(),
+            E4::responses(),
+            E5::responses(),
+            E6::responses(),
+            E7::responses(),
+            E8::responses(),
+        ])
+    }
+}
+
+/// Merge the responses sharing a status into a single one, with a `oneOf` of their bodies
+pub fn merge_responses<I>(responses: I) -> BTreeMap<String, RefOr<Response>>
+where
+    I: IntoIterator<Item = BTreeMap<String, RefOr<Response>>>,
+{
+    let mut merged: BTreeMap<String, RefOr<Response>> = BTreeMap::new();
+
+    for (status, response) in responses.into_iter().flatten() {
+        match (merged.get_mut(&status), response) {
+            (Some(RefOr::T(existing)), RefOr::T(response)) => merge_response(existing, response),
+            // Referenced responses can't be merged, the first one is kept
+            (Some(_), _) => {}
+            (None, response) => {
+                merged.insert(status, response);
             }
-        };
-        let status2 = match E2::responses().into_iter().next() {
-            Some((status1, _)) => status1,
-            _ => {
-                panic!("Not supported")
+        }
+    }
+
+    merged
+}
+
+fn merge_response(existing: &mut Response, response: Response) {
+    if !existing
+        .description
+        .split(" | ")
+        .any(|description| description == response.description)
+    {
+        existing.description = format!("{} | {}", existing.description, response.description);
+    }
+
+    for (name, header) in response.headers {
+        existing.headers.entry(name).or_insert(header);
+    }
+
+    for (content_type, content) in response.content {
+        match existing.content.get_mut(&content_type) {
+            Some(existing) => {
+                let schema = std::mem::replace(&mut existing.schema, ObjectBuilder::new().into());
+                existing.schema = one_of(schema, content.schema);
             }
-        };
+            None => {
+                existing.content.insert(content_type, content);
+            }
+        }
+    }
+}
 
-        debug_assert_eq!(
-            status1, status2,
-            "CombineErrors can only be used for errors with same status"
-        );
-
-        let schema: RefOr<Schema> = OneOfBuilder::new()
-            .item(Ref::from_schema_name(E1::schema().0))
-            .item(Ref::from_schema_name(E2::schema().0))
-            .into();
-
-        ResponsesBuilder::new()
-            .response(
-                status1,
-                ResponseBuilder::new()
-                    .content("json", ContentBuilder::new().schema(schema).build()),
-            )
-            .build()
-            .into()
+/// `oneOf` the schemas, flattening them if they're already a `oneOf`
+fn one_of(first: RefOr<Schema>, second: RefOr<Schema>) -> RefOr<Schema> {
+    let items = [first, second].into_iter().flat_map(|schema| match schema {
+        RefOr::T(Schema::OneOf(one_of)) => one_of.items,
+        schema => vec![schema],
+    });
+
+    items
+        .fold(OneOfBuilder::new(), |builder, item| builder.item(item))
+        .into()
+}
+
+impl IntoResponses for Nothing {
+    fn responses() -> BTreeMap<String, RefOr<Response>> {
+        // Used as a placeholder in `CombineErrors`
+        BTreeMap::new()
     }
 }
 
+/// Responses of the types usable in `transparent` variants, implemented by the derive so the docs
+/// of an inner error don't depend on the name or the path of its docs module
+pub trait ErrorDocs {
+    type All: IntoResponses;
+
+    /// Name of the type in the generic schema names(`JsonBatchResponse_User_AppErrors`), empty
+    /// for `Nothing`
+    const NAME: &'static str;
+}
+
+impl ErrorDocs for Nothing {
+    type All = Nothing;
+    const NAME: &'static str = "";
+}
+
+// Their status and content are only known at runtime, so there is nothing to document
+impl<T> ErrorDocs for JsonError<T> {
+    type All = Nothing;
+    const NAME: &'static str = "JsonError";
+}
+
+#[cfg(feature = "anyhow")]
+impl ErrorDocs for anyhow::Error {
+    type All = Nothing;
+    const NAME: &'static str = "anyhow_Error";
+}
+
+#[cfg(feature = "eyre")]
+impl ErrorDocs for eyre::Report {
+    type All = Nothing;
+    const NAME: &'static str = "eyre_Report";
+}
+
 impl<'__r, T, M> ToSchema<'__r> for JsonResponse<T, M>
 where
     T: ToSchema<'__r>,
     M: ToSchema<'__r>,
 {
     fn schema() -> (&'__r str, RefOr<Schema>) {
-        let null: RefOr<Schema> = ObjectBuilder::new()
-            .schema_type(SchemaType::Object)
-            .nullable(true)
-            .default(Some(serde_json::Value::Null))
-            .example(Some(serde_json::Value::Null))
-            .build()
-            .into();
+        (
+            generic_name("JsonResponse", &[T::schema().0, M::schema().0]),
+            json_response_schema::<T, M>(200).into(),
+        )
+    }
+}
+
+/// Schema of a `JsonResponse` responded with `status`
+pub fn json_response_schema<'__r, T, M>(status: u16) -> ObjectBuilder
+where
+    T: ToSchema<'__r>,
+    M: ToSchema<'__r>,
+{
+    let null: RefOr<Schema> = ObjectBuilder::new()
+        .schema_type(SchemaType::Object)
+        .nullable(true)
+        .default(Some(serde_json::Value::Null))
+        .example(Some(serde_json::Value::Null))
+        .build()
+        .into();
+
+    let obj = ObjectBuilder::new()
+        .property(
+            "status",
+            ObjectBuilder::new()
+                .schema_type(SchemaType::Integer)
+                .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32)))
+                .example(Some(status.into())),
+        )
+        .required("status");
+
+    let obj = match T::schema() {
+        ("", _) => obj.property("content", null.clone()),
+        // Lists are inlined, they're rarely registered as components
+        (_, schema @ RefOr::T(Schema::Array(_))) => {
+            obj.property("content", schema).required("content")
+        }
+        (name, _) => obj
+            .property("content", RefOr::Ref(Ref::from_schema_name(name)))
+            .required("content"),
+    };
+
+    match M::schema() {
+        ("", _) => obj.property("meta", null),
+        (name, _) => obj
+            .property("meta", RefOr::Ref(Ref::from_schema_name(name)))
+            .required("meta"),
+    }
+}
+
+/// Responses of the typed status wrappers, documented with their own status
+pub(crate) fn typed_responses<'__r, T, M>(status: StatusCode) -> BTreeMap<String, RefOr<Response>>
+where
+    T: ToSchema<'__r>,
+    M: ToSchema<'__r>,
+{
+    ResponsesBuilder::new()
+        .response(
+            status.as_str(),
+            ResponseBuilder::new()
+                .description(status.canonical_reason().unwrap_or_default())
+                .content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(json_response_schema::<T, M>(status.as_u16()))
+                        .build(),
+                ),
+        )
+        .build()
+        .into()
+}
+
+/// Name of a generic schema made of the names of its arguments, e.g. `JsonResponse_User_Nothing`
+///
+/// The `path` macro of utoipa refers to generic bodies by their bare name, so such bodies have to be
+/// inlined(`body = inline(JsonResponse<User>)`) for the name not to dangle.
+///
+/// `ToSchema` returns a borrowed name, so the names are built once and kept around.
+fn generic_name(name: &str, args: &[&str]) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let name = args.iter().fold(String::from(name), |mut name, arg| {
+        name.push('_');
+        name.push_str(if arg.is_empty() { "Nothing" } else { arg });
+        name
+    });
+
+    let mut names = NAMES.get_or_init(Default::default).lock().unwrap();
+    match names.get(name.as_str()) {
+        Some(name) => name,
+        None => {
+            let name = Box::leak(name.into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+impl ToSchema<'static> for Nothing {
+    fn schema() -> (&'static str, RefOr<Schema>) {
+        // A dummy implementation to make it recognizable by JsonResponse
+        ("", ObjectBuilder::new().into())
+    }
+}
+
+impl ToSchema<'static> for JsonErrors {
+    fn schema() -> (&'static str, RefOr<Schema>) {
+        let error = ObjectBuilder::new()
+            .property(
+                "status",
+                ObjectBuilder::new()
+                    .schema_type(SchemaType::Integer)
+                    .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32))),
+            )
+            .required("status")
+            .property("code", ObjectBuilder::new().schema_type(SchemaType::String))
+            .required("code")
+            .property("hint", ObjectBuilder::new().schema_type(SchemaType::String))
+            .property("content", ObjectBuilder::new().nullable(true))
+            .required("content");
 
         let obj = ObjectBuilder::new()
             .property(
                 "status",
                 ObjectBuilder::new()
                     .schema_type(SchemaType::Integer)
-                    .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32)))
-                    .example(Some(200.into())),
+                    .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32))),
             )
-            .required("status");
+            .required("status")
+            .property(
+                "code",
+                ObjectBuilder::new()
+                    .schema_type(SchemaType::String)
+                    .enum_values(Some(["multiple-errors"]))
+                    .example(Some("multiple-errors".into())),
+            )
+            .required("code")
+            .property("errors", ArrayBuilder::new().items(error))
+            .required("errors");
+
+        ("JsonErrors", obj.into())
+    }
+}
 
-        let obj = match T::schema() {
-            ("", _) => obj.property("content", null.clone()),
-            (name, _) => obj
-                .property("content", RefOr::Ref(Ref::from_schema_name(name)))
-                .required("content"),
+impl<'__r, T, E> ToSchema<'__r> for JsonBatchResponse<T, E>
+where
+    T: ToSchema<'__r>,
+    E: ErrorDocs,
+{
+    fn schema() -> (&'__r str, RefOr<Schema>) {
+        let integer = || {
+            ObjectBuilder::new()
+                .schema_type(SchemaType::Integer)
+                .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int32)))
         };
 
-        let obj = match M::schema() {
-            ("", _) => obj.property("meta", null.clone()),
-            (name, _) => obj
-                .property("meta", RefOr::Ref(Ref::from_schema_name(name)))
-                .required("meta"),
+        let content: RefOr<Schema> = match T::schema() {
+            ("", _) => ObjectBuilder::new().nullable(true).into(),
+            (name, _) => Ref::from_schema_name(name).into(),
         };
 
-        ("JsonResponse", obj.into())
+        let success = ObjectBuilder::new()
+            .property("index", integer())
+            .required("index")
+            .property("id", ObjectBuilder::new().schema_type(SchemaType::String))
+            .property("status", integer())
+            .required("status")
+            .property("content", content)
+            .required("content");
+
+        // Failed items are the errors of `E` next to their index and id
+        let errors = <E::All as IntoResponses>::responses()
+            .into_values()
+            .filter_map(|response| match response {
+                RefOr::T(response) => Some(response),
+                RefOr::Ref(_) => None,
+            })
+            .filter_map(|response| {
+                response
+                    .content
+                    .get("application/json")
+                    .map(|content| content.schema.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let failure: RefOr<Schema> = if errors.is_empty() {
+            ObjectBuilder::new()
+                .property("index", integer())
+                .required("index")
+                .property("id", ObjectBuilder::new().schema_type(SchemaType::String))
+                .property("status", integer())
+                .required("status")
+                .property("code", ObjectBuilder::new().schema_type(SchemaType::String))
+                .required("code")
+                .property("hint", ObjectBuilder::new().schema_type(SchemaType::String))
+                .property("content", ObjectBuilder::new().nullable(true))
+                .required("content")
+                .into()
+        } else {
+            let item = ObjectBuilder::new()
+                .property("index", integer())
+                .required("index")
+                .property("id", ObjectBuilder::new().schema_type(SchemaType::String));
+            let error: RefOr<Schema> = match errors.len() {
+                1 => errors.into_iter().next().unwrap(),
+                _ => errors
+                    .into_iter()
+                    .fold(OneOfBuilder::new(), |one_of, error| one_of.item(error))
+                    .into(),
+            };
+            AllOfBuilder::new().item(item).item(error).into()
+        };
+
+        let meta = ObjectBuilder::new()
+            .property("total", integer())
+            .required("total")
+            .property("succeeded", integer())
+            .required("succeeded")
+            .property("failed", integer())
+            .required("failed");
+
+        let obj = ObjectBuilder::new()
+            .property("status", integer().example(Some(207.into())))
+            .required("status")
+            .property(
+                "content",
+                ArrayBuilder::new().items(OneOfBuilder::new().item(success).item(failure)),
+            )
+            .required("content")
+            .property("meta", meta)
+            .required("meta");
+
+        let name = match E::NAME {
+            "" => generic_name("JsonBatchResponse", &[T::schema().0]),
+            docs => generic_name("JsonBatchResponse", &[T::schema().0, docs]),
+        };
+
+        (name, obj.into())
     }
 }
 
-impl ToSchema<'static> for Nothing {
+impl<'__r, T> ToSchema<'__r> for JsonList<T>
+where
+    T: ToSchema<'__r>,
+{
+    fn schema() -> (&'__r str, RefOr<Schema>) {
+        let (name, schema) = T::schema();
+        let items = match name {
+            "" => schema,
+            name => Ref::from_schema_name(name).into(),
+        };
+
+        (
+            generic_name("JsonList", &[name]),
+            ArrayBuilder::new().items(items).into(),
+        )
+    }
+}
+
+impl ToSchema<'static> for JsonListMeta {
     fn schema() -> (&'static str, RefOr<Schema>) {
-        // A dummy implementation to make it recognizable by JsonResponse
-        ("", ObjectBuilder::new().into())
+        let obj = ObjectBuilder::new()
+            .property(
+                "total",
+                ObjectBuilder::new()
+                    .schema_type(SchemaType::Integer)
+                    .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int64)))
+                    .description(Some("Total number of the items")),
+            )
+            .property(
+                "next",
+                ObjectBuilder::new()
+                    .schema_type(SchemaType::String)
+                    .description(Some("Link to the next page")),
+            )
+            .property(
+                "prev",
+                ObjectBuilder::new()
+                    .schema_type(SchemaType::String)
+                    .description(Some("Link to the previous page")),
+            );
+
+        ("JsonListMeta", obj.into())
     }
 }
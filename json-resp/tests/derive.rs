@@ -0,0 +1,180 @@
+use std::{error::Error, fmt};
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use json_resp::{DynJsonError, JsonError};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+async fn render(error: impl IntoResponse) -> (StatusCode, HeaderMap, Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, headers, serde_json::from_slice(&body).unwrap())
+}
+
+#[derive(Debug, Serialize)]
+struct RateLimitInfo {
+    retry_after: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonError)]
+#[json_error(request, status = 404, code = "missing")]
+struct Missing {
+    name: String,
+}
+
+impl fmt::Display for Missing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is missing", self.name)
+    }
+}
+
+impl Error for Missing {}
+
+fn missing(name: &str) -> Missing {
+    Missing { name: name.into() }
+}
+
+#[derive(Debug, JsonError)]
+enum LookupErrors {
+    #[json_error(request, status = 404, code = "not-found", hint_from_display)]
+    NotFound(String),
+}
+
+impl fmt::Display for LookupErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "{} was not found", name),
+        }
+    }
+}
+
+#[derive(Debug, JsonError)]
+enum AuthErrors {
+    #[json_error(request, status = 401, code = "unauthorized", header("WWW-Authenticate" = "Bearer"))]
+    Unauthorized,
+
+    #[json_error(request, status = StatusCode::TOO_MANY_REQUESTS, code = "rate-limited", header("Retry-After" = retry_after))]
+    RateLimited(RateLimitInfo),
+}
+
+#[derive(Debug, JsonError)]
+#[json_error(internal_code = "app-error")]
+enum AppErrors {
+    #[json_error(request, status = 404, code = "not-found", hint = "check the id")]
+    NotFound,
+
+    #[json_error(request, status = 422, code = "invalid")]
+    Invalid(Vec<&'static str>),
+
+    #[json_error(internal)]
+    Database(Missing),
+
+    #[json_error(internal, status = 503, code = "upstream-unavailable", header("Retry-After" = "30"))]
+    Upstream,
+
+    #[json_error(transparent)]
+    Auth(AuthErrors),
+
+    #[json_error(internal, promote(Missing))]
+    Other(anyhow::Error),
+}
+
+#[derive(Debug, Serialize, JsonError)]
+#[json_error(request, status = 429, code = "slow-down")]
+struct SlowDown {
+    retry_after: u64,
+}
+
+#[tokio::test]
+async fn request_errors_respond_with_their_status_code_and_hint() {
+    let (status, _, body) = render(AppErrors::NotFound).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body,
+        json!({"status": 404, "code": "not-found", "hint": "check the id", "content": null})
+    );
+
+    let (status, _, body) = render(AppErrors::Invalid(vec!["email"])).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["content"], json!(["email"]));
+}
+
+#[tokio::test]
+async fn hint_from_display_uses_the_display_of_the_error() {
+    let (_, _, body) = render(LookupErrors::NotFound("user".into())).await;
+    assert_eq!(body["hint"], "user was not found");
+}
+
+#[tokio::test]
+async fn internal_errors_hide_their_content() {
+    let (status, _, body) = render(AppErrors::Database(missing("table"))).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["code"], "app-error");
+    assert_eq!(body["content"], Value::Null);
+    assert!(!body.to_string().contains("table"));
+}
+
+#[tokio::test]
+async fn internal_errors_can_set_their_status_code_and_headers() {
+    let (status, headers, body) = render(AppErrors::Upstream).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "upstream-unavailable");
+    assert_eq!(headers[header::RETRY_AFTER], "30");
+}
+
+#[tokio::test]
+async fn headers_are_taken_from_literals_and_fields() {
+    let (_, headers, _) = render(AuthErrors::Unauthorized).await;
+    assert_eq!(headers[header::WWW_AUTHENTICATE], "Bearer");
+
+    let (status, headers, body) =
+        render(AuthErrors::RateLimited(RateLimitInfo { retry_after: 12 })).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(headers[header::RETRY_AFTER], "12");
+    assert_eq!(body["content"], json!({"retry_after": 12}));
+}
+
+#[tokio::test]
+async fn transparent_variants_respond_with_the_inner_error() {
+    let (status, headers, body) = render(AppErrors::Auth(AuthErrors::Unauthorized)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "unauthorized");
+    assert_eq!(headers[header::WWW_AUTHENTICATE], "Bearer");
+}
+
+#[tokio::test]
+async fn promote_responds_with_a_source_of_the_content() {
+    let error = anyhow::Error::new(missing("user")).context("loading the profile");
+    let (status, _, body) = render(AppErrors::Other(error)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "missing");
+    assert_eq!(body["content"], json!({"name": "user"}));
+
+    let (status, _, body) = render(AppErrors::Other(anyhow::anyhow!("timeout"))).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["code"], "app-error");
+}
+
+#[tokio::test]
+async fn structs_are_the_content_of_their_error() {
+    let (status, _, body) = render(SlowDown { retry_after: 5 }).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        body,
+        json!({"status": 429, "code": "slow-down", "content": {"retry_after": 5}})
+    );
+}
+
+#[tokio::test]
+async fn dyn_errors_keep_the_response() {
+    let error: DynJsonError = AuthErrors::Unauthorized.into();
+    let (status, headers, body) = render(error).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "unauthorized");
+    assert_eq!(headers[header::WWW_AUTHENTICATE], "Bearer");
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    error_handling::HandleErrorLayer,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use json_resp::{handle_error, panic, ErrorIdLayer, JsonError};
+use serde_json::Value;
+use tower::{ServiceBuilder, ServiceExt};
+
+#[derive(Debug, JsonError)]
+enum AppErrors {
+    #[json_error(internal)]
+    Internal,
+}
+
+async fn call(app: Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn boom() -> &'static str {
+    panic!("boom")
+}
+
+async fn slow() -> &'static str {
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    "ok"
+}
+
+fn get_request(uri: &str) -> Request<Body> {
+    Request::builder().uri(uri).body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn error_ids_are_taken_from_the_request_id() {
+    let app = Router::new()
+        .route("/", get(|| async { AppErrors::Internal }))
+        .layer(ErrorIdLayer::new());
+
+    let request = Request::builder()
+        .uri("/")
+        .header("x-request-id", "request-1")
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = call(app.clone(), request).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["meta"]["error_id"], "request-1");
+
+    let (_, body) = call(app, get_request("/")).await;
+    assert!(body["meta"]["error_id"].is_string());
+}
+
+#[tokio::test]
+async fn panics_respond_with_internal_errors() {
+    let app = Router::new()
+        .route("/", get(boom))
+        .route(
+            "/unavailable",
+            get(boom).layer(
+                panic::PanicHandler::new()
+                    .status(StatusCode::SERVICE_UNAVAILABLE)
+                    .code("unavailable")
+                    .layer(),
+            ),
+        )
+        .layer(panic::layer());
+
+    let (status, body) = call(app.clone(), get_request("/")).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["code"], "internal-error");
+    assert!(!body.to_string().contains("boom"));
+
+    let (status, body) = call(app, get_request("/unavailable")).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["code"], "unavailable");
+}
+
+#[tokio::test]
+async fn timeouts_respond_with_request_timeout() {
+    let app = Router::new()
+        .route(
+            "/",
+            get(slow).layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(handle_error::handle_error))
+                    .timeout(Duration::from_millis(10)),
+            ),
+        )
+        .route(
+            "/gateway",
+            get(slow).layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(handle_error::handle_error_as_gateway))
+                    .timeout(Duration::from_millis(10)),
+            ),
+        );
+
+    let (status, body) = call(app.clone(), get_request("/")).await;
+    assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+    assert_eq!(body["code"], "request-timeout");
+
+    let (status, body) = call(app, get_request("/gateway")).await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(body["code"], "gateway-timeout");
+}
@@ -0,0 +1,137 @@
+use axum::http::StatusCode;
+use json_resp::{Created, JsonError, JsonResponse, JsonSuccess};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Serialize, ToSchema)]
+struct User {
+    name: String,
+}
+
+#[derive(JsonError)]
+#[allow(dead_code)]
+enum UserErrors {
+    #[json_error(request, status = 404, code = "user-not-found")]
+    NotFound,
+
+    #[json_error(request, status = StatusCode::CONFLICT, code = "user-exists", hint = "pick another name")]
+    Exists,
+
+    #[json_error(internal)]
+    Internal,
+}
+
+#[derive(JsonSuccess)]
+#[allow(dead_code)]
+enum UserResponses {
+    Found(User),
+    #[json_success(status = 201)]
+    Created(User),
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}",
+    responses(
+        (status = 200, body = inline(JsonResponse<User>)),
+        UserErrorsOai::All
+    )
+)]
+#[allow(dead_code)]
+async fn get_user() {}
+
+#[utoipa::path(post, path = "/users", responses(Created<User>, UserErrorsOai::Exists))]
+#[allow(dead_code)]
+async fn create_user() {}
+
+#[utoipa::path(put, path = "/users", responses(UserResponses))]
+#[allow(dead_code)]
+async fn put_user() {}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_user, create_user, put_user),
+    components(schemas(
+        User,
+        UserErrorsOai::NotFound,
+        UserErrorsOai::Exists,
+        UserErrorsOai::InternalError
+    ))
+)]
+struct Api;
+
+fn refs(value: &Value, found: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => found.push(reference.clone()),
+                    _ => refs(value, found),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| refs(value, found)),
+        _ => {}
+    }
+}
+
+fn openapi() -> Value {
+    serde_json::to_value(Api::openapi()).unwrap()
+}
+
+#[test]
+fn every_ref_is_registered() {
+    let openapi = openapi();
+    let mut found = Vec::new();
+    refs(&openapi["paths"], &mut found);
+    assert!(!found.is_empty());
+
+    for reference in found {
+        let name = reference
+            .strip_prefix("#/components/schemas/")
+            .unwrap_or_else(|| panic!("unexpected ref {}", reference));
+        assert!(
+            openapi["components"]["schemas"].get(name).is_some(),
+            "{} is not registered",
+            reference
+        );
+    }
+}
+
+#[test]
+fn errors_are_named_after_their_type() {
+    let schemas = &openapi()["components"]["schemas"];
+    for name in [
+        "UserErrors_NotFound",
+        "UserErrors_Exists",
+        "UserErrors_InternalError",
+    ] {
+        assert!(schemas.get(name).is_some(), "{} is missing", name);
+    }
+    assert_eq!(
+        schemas["UserErrors_Exists"]["properties"]["hint"]["type"],
+        "string"
+    );
+}
+
+#[test]
+fn errors_sharing_a_status_are_combined() {
+    let openapi = openapi();
+    let responses = &openapi["paths"]["/users/{id}"]["get"]["responses"];
+    assert!(responses.get("404").is_some());
+    assert!(responses.get("409").is_some());
+    assert!(responses.get("500").is_some());
+
+    let responses = &openapi["paths"]["/users"]["put"]["responses"];
+    assert!(responses.get("200").is_some());
+    assert!(responses.get("201").is_some());
+}
+
+#[test]
+fn generic_schemas_are_named_after_their_arguments() {
+    assert_eq!(
+        <JsonResponse<User> as ToSchema>::schema().0,
+        "JsonResponse_User_Nothing"
+    );
+}
@@ -0,0 +1,115 @@
+use std::{fmt, sync::OnceLock};
+
+use axum::{http::StatusCode, response::IntoResponse};
+use json_resp::{
+    reporter::{self, MemoryReporter, ReportedError},
+    JsonError, ResultExt,
+};
+
+#[derive(Debug)]
+struct Timeout;
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("connection timed out")
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+#[derive(Debug, JsonError)]
+enum UserErrors {
+    #[json_error(request, status = 404, code = "user-not-found")]
+    NotFound,
+
+    #[json_error(internal)]
+    Database(Timeout),
+}
+
+#[derive(Debug, JsonError)]
+enum AuthErrors {
+    #[json_error(request, status = 401, code = "unauthorized")]
+    Unauthorized,
+}
+
+#[derive(Debug, JsonError)]
+enum AppErrors {
+    #[json_error(transparent)]
+    Auth(AuthErrors),
+}
+
+/// The reporter can only be registered once per process, so the tests share it and look at the
+/// reports of their own types
+fn reporter() -> &'static MemoryReporter {
+    static REPORTER: OnceLock<MemoryReporter> = OnceLock::new();
+    REPORTER.get_or_init(|| {
+        let reporter = MemoryReporter::new();
+        reporter::set_reporter(reporter.clone()).unwrap();
+        reporter
+    })
+}
+
+fn reports_of(type_name: &str) -> Vec<ReportedError> {
+    reporter()
+        .reports()
+        .into_iter()
+        .filter(|report| report.type_name == type_name)
+        .collect()
+}
+
+#[test]
+fn derived_errors_are_reported() {
+    reporter();
+    UserErrors::NotFound.into_response();
+    UserErrors::Database(Timeout).into_response();
+
+    assert_eq!(
+        reports_of("UserErrors"),
+        vec![
+            ReportedError {
+                type_name: "UserErrors",
+                variant: Some("NotFound"),
+                code: "user-not-found",
+                status: StatusCode::NOT_FOUND,
+                internal: false,
+                error: None,
+            },
+            ReportedError {
+                type_name: "UserErrors",
+                variant: Some("Database"),
+                code: "internal-error",
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+                internal: true,
+                error: Some(String::from("connection timed out")),
+            },
+        ]
+    );
+}
+
+#[test]
+fn transparent_variants_are_reported_by_the_inner_error() {
+    reporter();
+    AppErrors::Auth(AuthErrors::Unauthorized).into_response();
+
+    assert!(reports_of("AppErrors").is_empty());
+    let reports = reports_of("AuthErrors");
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].variant, Some("Unauthorized"));
+}
+
+#[test]
+fn internal_results_are_reported() {
+    reporter();
+    let result: Result<(), _> = Err(Timeout);
+    result.internal().unwrap_err().into_response();
+
+    let reports: Vec<_> = reporter()
+        .reports()
+        .into_iter()
+        .filter(|report| report.error.as_deref() == Some("connection timed out"))
+        .filter(|report| report.type_name != "UserErrors")
+        .collect();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].internal);
+    assert_eq!(reports[0].status, StatusCode::INTERNAL_SERVER_ERROR);
+}
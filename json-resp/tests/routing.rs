@@ -0,0 +1,77 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use json_resp::{normalize::NormalizeErrorsLayer, RouterExt};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn call(app: Router, method: Method, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn router() -> Router {
+    Router::new().route("/", get(|| async { "ok" }))
+}
+
+#[tokio::test]
+async fn fallback_responds_with_route_not_found() {
+    let (status, body) = call(router().json_fallbacks(), Method::GET, "/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "route-not-found");
+}
+
+#[tokio::test]
+async fn method_not_allowed_lists_the_allowed_methods() {
+    let (status, body) = call(router().json_fallbacks(), Method::POST, "/").await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(body["code"], "method-not-allowed");
+    assert_eq!(body["content"], json!({"allowed": ["GET", "HEAD"]}));
+}
+
+#[tokio::test]
+async fn normalize_composes_with_json_fallbacks() {
+    let app = router().layer(NormalizeErrorsLayer::new()).json_fallbacks();
+
+    let (status, body) = call(app.clone(), Method::POST, "/").await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(body["code"], "method-not-allowed");
+    assert_eq!(body["content"], json!({"allowed": ["GET", "HEAD"]}));
+
+    let (status, body) = call(app, Method::GET, "/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "route-not-found");
+}
+
+#[tokio::test]
+async fn normalize_wraps_foreign_errors() {
+    let app = Router::new()
+        .route(
+            "/",
+            get(|| async {
+                (
+                    StatusCode::CONFLICT,
+                    [(header::CONTENT_TYPE, "text/plain")],
+                    "no",
+                )
+            }),
+        )
+        .layer(NormalizeErrorsLayer::new().code(StatusCode::CONFLICT, "duplicate"));
+
+    let (status, body) = call(app, Method::GET, "/").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        body,
+        json!({"status": 409, "code": "duplicate", "content": null})
+    );
+}